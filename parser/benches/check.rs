@@ -0,0 +1,78 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use sysdc_parser::Parser;
+
+// units個のUnitからなるシステムを生成する
+// ※各Unitはfuncs個の関数を持つModuleと，関数から参照されるDataを持つ
+fn generate_system(units: usize, funcs: usize) -> Vec<(String, String)> {
+    let mut programs = vec![];
+    for unit in 0..units {
+        let mut program = format!("unit bench{};\n\n", unit);
+        if unit > 0 {
+            program += &format!("from bench{} import Box{};\n\n", unit - 1, unit - 1);
+        }
+        program += &format!("pub data Box{} {{\n    x: i32,\n    y: i32\n}}\n\n", unit);
+        program += &format!("module Module{} {{\n", unit);
+        for func in 0..funcs {
+            let data = format!("Box{}", if unit > 0 { unit - 1 } else { unit });
+            program += &format!(
+                "    func f{func}(a: {data}, b: i32) -> i32 {{
+        @return r
+        @spawn r: i32 {{
+            use a;
+            let v = g{func}(a.x);
+            return v;
+        }}
+    }}
+
+    func g{func}(x: i32) -> i32 {{
+        @return x
+    }}
+
+"
+            );
+        }
+        program += "}\n";
+        programs.push((format!("bench{}.def", unit), program));
+    }
+    programs
+}
+
+fn check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check");
+    group.sample_size(10);
+    // ※関数の数を増やす場合と，Unitの数を増やす場合をそれぞれ計測する
+    let sizes = [
+        (10, 10),
+        (10, 50),
+        (10, 100),
+        (10, 200),
+        (50, 10),
+        (100, 10),
+        (200, 10),
+    ];
+    for (units, funcs) in sizes {
+        let programs = generate_system(units, funcs);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}units/{}funcs", units, funcs)),
+            &programs,
+            |b, programs| {
+                b.iter_batched(
+                    || {
+                        let mut parser = Parser::default();
+                        for (filename, program) in programs {
+                            parser.parse(filename.clone(), program).unwrap();
+                        }
+                        parser
+                    },
+                    |parser| parser.check().unwrap(),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, check);
+criterion_main!(benches);
@@ -1,35 +1,66 @@
 mod utils;
+mod cycle;
 mod resolve;
 mod matches;
+mod rules;
+mod recursion;
 
+use super::error::PError;
 use super::structure::unchecked;
 use super::structure::SysDCSystem;
+use cycle::ImportGraph;
 use matches::TypeMatchChecker;
+use recursion::DataGraph;
 use resolve::TypeResolver;
+use rules::RuleChecker;
 use utils::define::DefinesManager;
 
-pub fn check(system: unchecked::SysDCSystem) -> anyhow::Result<SysDCSystem> {
+// 検査はエラーが見つかっても可能な限り続けられ，見つかったエラーはまとめて返される
+pub fn check(system: unchecked::SysDCSystem) -> Result<SysDCSystem, Vec<anyhow::Error>> {
     // 0. 準備
-    let def_manager = DefinesManager::new(&system)?;
-    let mut imports = vec![];
+    let (def_manager, mut errors) = DefinesManager::new(&system);
     for unit in &system.units {
         for import in &unit.imports {
-            def_manager.check_can_import(import, &vec![])?;
-            imports.push((*import).clone());
+            if let Err(err) = def_manager.check_can_import(import, &unit.name) {
+                errors.push(err);
+            }
         }
     }
+    let rule_checker = RuleChecker::new(&system, &def_manager);
+    errors.extend(rule_checker.check_imports(&system));
 
     // 1. 型解決
-    let system = TypeResolver::resolve(system, &def_manager, &imports)?;
+    let (system, resolve_errors) = TypeResolver::resolve(system, &def_manager);
+    errors.extend(resolve_errors);
 
     // 2. 型適合チェック
-    TypeMatchChecker::check(&system, &def_manager, &imports)?;
+    errors.extend(TypeMatchChecker::check(&system, &def_manager));
+
+    // 3. Unit間の依存に関する規則のチェック
+    errors.extend(rule_checker.check(&system));
+
+    // 4. Dataの再帰のチェック
+    errors.extend(DataGraph::new(&system).find_cycles());
+
+    if errors.is_empty() {
+        Ok(system)
+    } else {
+        Err(errors)
+    }
+}
 
-    Ok(system)
+// Unit間のimportの依存関係を調べ，循環しているimportを報告する
+// ※循環は検査の成否に関わらず報告され，エラーとするか警告とするかは呼び出し側(Lintの設定)に従う
+pub fn find_import_cycles(system: &unchecked::SysDCSystem) -> Vec<PError> {
+    ImportGraph::new(system).find_cycles()
 }
 
 #[cfg(test)]
 mod test {
+    use crate::error::{Diagnostic, PError};
+    use crate::name::Name;
+    use crate::structure::{SysDCAnnotation, SysDCSpawnDetail};
+    use crate::types::TypeKind;
     use crate::Parser;
 
     #[test]
@@ -65,6 +96,7 @@ mod test {
     }
 
     #[test]
+    #[should_panic]
     fn data_has_recursive_member() {
         let program = "
             unit test;
@@ -78,74 +110,93 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn data_has_undefined_typed_member() {
+    fn data_has_mutually_recursive_member() {
         let program = "
             unit test;
 
-            data Test {
-                a: i32,
-                b: Unknown
+            data A {
+                b: B
+            }
+
+            data B {
+                c: C
+            }
+
+            data C {
+                a: A
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn module_simple() {
+    fn data_has_recursive_member_with_indirection() {
         let program = "
             unit test;
 
             data A {
-                a: i32,
-                b: i32
+                a: Option<A>,
+                b: List<B>
             }
 
-            module TestModule {
-                func test(a: A) -> A {
-                    @return a
-                }
+            data B {
+                a: A,
+                b: Map<string, B>
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn module_with_affect_1() {
+    #[should_panic]
+    fn data_has_recursive_member_through_type_param() {
         let program = "
             unit test;
 
-            data A {}
-
-            data B {}
-
-            module TestModuleA {
-                proc test(a: A, b: B) {}
+            data Page<T> {
+                item: T
             }
 
-            module TestModuleB {
-                proc test(a: A, b: B) {
-                    @affect TestModuleA.test(a, b)
-                }
+            data A {
+                p: Page<A>
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn module_with_affect_2() {
+    #[should_panic]
+    fn data_has_recursive_member_through_nested_type_param() {
         let program = "
             unit test;
 
-            data A {}
+            data Wrapper<T> {
+                value: T
+            }
 
-            data B {}
+            data Page<T> {
+                item: Wrapper<T>
+            }
 
-            module TestModuleA {
-                proc test(a: A, b: B) {
-                    @affect test2(a, b)
-                }
+            data A {
+                p: Page<Wrapper<A>>
+            }
+        ";
+        check(vec![program]);
+    }
 
-                proc test2(a: A, b: B) {}
+    #[test]
+    fn data_has_recursive_member_through_type_param_with_indirection() {
+        let program = "
+            unit test;
+
+            data Page<T> {
+                items: List<T>,
+                next: Option<Page<T>>
+            }
+
+            data A {
+                p: Page<A>
             }
         ";
         check(vec![program]);
@@ -153,87 +204,126 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn module_with_affect_failure_1() {
+    fn data_extends_recursive_data() {
         let program = "
             unit test;
 
-            data A {}
+            data A {
+                b: B
+            }
 
-            data B {}
+            data B = A + {
+                c: i32
+            }
+        ";
+        check(vec![program]);
+    }
 
-            module TestModuleA {
-                proc test(a: A, b: B) {
-                    @affect test2(a)
-                }
+    #[test]
+    #[should_panic]
+    fn data_has_undefined_typed_member() {
+        let program = "
+            unit test;
 
-                proc test2(a: A, b: B) {}
+            data Test {
+                a: i32,
+                b: Unknown
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn module_with_modify_1() {
+    fn enum_simple() {
         let program = "
             unit test;
 
-            data A {}
+            data Reason {
+                code: i32
+            }
 
-            data B {}
+            enum Status {
+                Active,
+                Suspended(reason: Reason, days: i32)
+            }
 
-            data C {}
+            data User {
+                status: Status
+            }
 
-            module TestModule {
-                proc test(a: A, b: B, c: C) {
-                    @modify a {
-                        use b, c;
+            module UserModule {
+                func current(user: User) -> Status {
+                    @return s
+
+                    @spawn s: Status {
+                        use user;
+                        let s = get(user.status);
+                        return s;
                     }
                 }
+
+                func get(status: Status) -> Status {
+                    @return status
+                }
             }
         ";
-        check(vec![program]);
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let unit = &system.units[0];
+        let variant = &unit.enums[0].variants[1];
+        assert_eq!(variant.members[0].1.kind, TypeKind::Data);
+        assert_eq!(variant.members[1].1.kind, TypeKind::Int32);
+        assert_eq!(unit.data[1].members[0].1.kind, TypeKind::Enum);
+        assert_eq!(unit.modules[0].functions[0].returns.1.kind, TypeKind::Enum);
     }
 
     #[test]
-    fn module_with_modify_2() {
-        let program = "
+    fn import_enum_in_other_unit() {
+        let program1 = "
             unit test;
 
-            data A {}
-
-            data B {}
+            from test2 import Status;
 
-            data C {}
+            data User {
+                status: Status
+            }
+        ";
+        let program2 = "
+            unit test2;
 
-            module TestModule {
-                proc test(a: A, b: B, c: C) {
-                    @modify a
-                    @modify b
-                    @modify c
-                }
+            pub enum Status {
+                Active,
+                Suspended(days: i32)
             }
         ";
-        check(vec![program]);
+        check(vec![program1, program2]);
     }
 
     #[test]
     #[should_panic]
-    fn module_with_modify_failure_1() {
+    fn enum_has_undefined_typed_member() {
         let program = "
             unit test;
 
-            data A {}
-
-            data B {
-                a: i32
+            enum Status {
+                Active,
+                Suspended(reason: Unknown)
             }
+        ";
+        check(vec![program]);
+    }
 
-            module TestModule {
-                proc test(a: A, b: B, c: C) {
-                    @modify a {
-                        use b.a;
-                    }
-                }
+    #[test]
+    #[should_panic]
+    fn enum_has_duplicated_variant() {
+        let program = "
+            unit test;
+
+            enum Status {
+                Active,
+                Active
             }
         ";
         check(vec![program]);
@@ -241,113 +331,138 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn module_with_modify_failure_2() {
+    fn enum_member_access() {
         let program = "
             unit test;
 
-            data A {}
-
-            data B {
-                a: i32
+            enum Status {
+                Suspended(days: i32)
             }
 
-            module TestModule {
-                proc test(a: A, b: B, c: C) {
-                    @modify a {
-                        use b, c;
+            module M {
+                func f(status: Status) -> i32 {
+                    @return d
+
+                    @spawn d: i32 {
+                        use status;
+                        let d = get(status.days);
+                        return d;
                     }
                 }
+
+                func get(days: i32) -> i32 {
+                    @return days
+                }
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn module_with_spawn() {
+    fn generic_types() {
         let program = "
             unit test;
 
-            data A {
-                a: B,
-                b: B
-            }
-
-            data B {
-                a: C,
-                b: C
+            data User {
+                id: i32
             }
 
-            data C {
-                a: i32,
-                b: i32
+            data Group {
+                users: List<User>,
+                scores: Map<i32, Option<List<f32>>>
             }
 
-            module TestModule {
-                func test(a: A) -> A {
-                    @return b
+            module GroupModule {
+                func members(group: Group) -> List<User> {
+                    @return users
 
-                    @spawn b: A {
-                        use a;
-                        let tmp = receiveA(a);
-                        let tmp1 = receiveB(a.a);
-                        let tmp2 = receiveB(a.b);
-                        let tmp3 = receiveC(a.a.a);
-                        let tmp4 = receiveC(a.a.b);
-                        let tmp5 = receiveC(a.b.a);
-                        let tmp6 = receiveC(a.b.b);
-                        let tmp7 = receiveInt32(a.a.a.a);
-                        let tmp8 = receiveInt32(a.a.a.b);
-                        let tmp9 = receiveInt32(a.a.b.a);
-                        let tmp10 = receiveInt32(a.a.b.b);
-                        let tmp11 = receiveInt32(a.b.a.a);
-                        let tmp12 = receiveInt32(a.b.a.b);
-                        let tmp13 = receiveInt32(a.b.b.a);
-                        let tmp14 = receiveInt32(a.b.b.b);
+                    @spawn users: List<User> {
+                        use group;
+                        let users = copy(group.users);
+                        return users;
                     }
                 }
 
-                func receiveA(a: A) -> i32 {
-                    @return tmp
-                    @spawn tmp: i32
-                }
-
-                func receiveB(b: B) -> i32 {
-                    @return tmp
-                    @spawn tmp: i32
+                func copy(users: List<User>) -> List<User> {
+                    @return users
                 }
+            }
+        ";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let group = &system.units[0].data[1];
+        let (_, users) = &group.members[0];
+        assert_eq!(users.kind, TypeKind::List);
+        assert_eq!(users.params[0].kind, TypeKind::Data);
+        assert_eq!(users.params[0].refs.as_ref().unwrap().name, "User");
+
+        let (_, scores) = &group.members[1];
+        assert_eq!(scores.kind, TypeKind::Map);
+        assert_eq!(scores.params[0].kind, TypeKind::Int32);
+        assert_eq!(scores.params[1].kind, TypeKind::Option);
+        assert_eq!(scores.params[1].params[0].kind, TypeKind::List);
+        assert_eq!(scores.params[1].params[0].params[0].kind, TypeKind::Float32);
+    }
 
-                func receiveC(c: C) -> i32 {
-                    @return tmp
-                    @spawn tmp: i32
-                }
+    #[test]
+    fn extended_primitive_types() {
+        let program = "
+            unit test;
 
-                func receiveInt32(i: i32) -> i32 {
-                    @return tmp
-                    @spawn tmp: i32
-                }
+            data Record {
+                id: u64,
+                offset: i64,
+                score: f64,
+                title: string,
+                body: bytes,
+                extra: any
             }
         ";
-        check(vec![program])
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let kinds = system.units[0].data[0]
+            .members
+            .iter()
+            .map(|(_, types)| types.kind.clone())
+            .collect::<Vec<TypeKind>>();
+        assert_eq!(
+            kinds,
+            vec![
+                TypeKind::UInt64,
+                TypeKind::Int64,
+                TypeKind::Float64,
+                TypeKind::String,
+                TypeKind::Bytes,
+                TypeKind::Any
+            ]
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn module_with_spawn_failure_1() {
+    fn any_is_compatible_with_all_types() {
         let program = "
             unit test;
 
-            data A {
-                a: i32,
-                b: i32
+            data Box {
+                x: i32
             }
 
-            module TestModule {
-                func test(a: A) -> A {
-                    @return b
+            module Printer {
+                proc print(value: any) {}
 
-                    @spawn b: A {
-                        use aaa;
-                    }
+                proc print_all(values: List<any>) {}
+
+                proc print_box(box: Box, boxes: List<Box>) {
+                    @affect print(box)
+                    @affect print_all(boxes)
+                }
+
+                func wrap(box: Box) -> any {
+                    @return box
                 }
             }
         ";
@@ -356,28 +471,15 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn module_with_spawn_failure_2() {
+    fn extended_primitive_type_unmatch() {
         let program = "
             unit test;
 
-            data A {
-                a: i32,
-                b: i32
-            }
-
-            module TestModule {
-                func test(a: A) -> A {
-                    @return b
-
-                    @spawn b: A {
-                        use a;
-                        let tmp = receiveInt32(a.c);
-                    }
-                }
+            module Store {
+                proc save(id: u64) {}
 
-                func receiveInt32(i: i32) -> i32 {
-                    @return tmp
-                    @spawn tmp: i32
+                proc save_all(id: u32) {
+                    @affect save(id)
                 }
             }
         ";
@@ -386,115 +488,207 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn module_with_spawn_failure_3() {
+    fn generic_type_has_undefined_param() {
         let program = "
             unit test;
 
-            data A {
-                a: B,
-                b: B
-            }
-
-            data B {
-                a: C,
-                b: C
+            data Group {
+                users: List<User>
             }
+        ";
+        check(vec![program]);
+    }
 
-            data C {
-                a: i32,
-                b: i32
+    #[test]
+    #[should_panic]
+    fn generic_type_params_length_not_match() {
+        let program = "
+            unit test;
+
+            data Group {
+                scores: Map<i32>
             }
+        ";
+        check(vec![program]);
+    }
 
-            module TestModule {
-                func test(a: A) -> A {
-                    @return b
+    #[test]
+    #[should_panic]
+    fn params_on_non_generic_type() {
+        let program = "
+            unit test;
 
-                    @spawn b: A {
-                        use a.b.a.c;
-                    }
-                }
+            data Group {
+                size: i32<i32>
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn ref_function_using_completed_name_1() {
+    #[should_panic]
+    fn generic_type_unmatch() {
         let program = "
             unit test;
 
-            data A {}
+            module M {
+                func f(values: List<i32>) -> List<f32> {
+                    @return result
 
-            module AModule {
-                func new() -> A {
-                    @return a
+                    @spawn result: List<f32> {
+                        use values;
+                        let result = copy(values);
+                        return result;
+                    }
+                }
 
-                    @spawn a: A
+                func copy(values: List<f32>) -> List<f32> {
+                    @return values
                 }
             }
+        ";
+        check(vec![program]);
+    }
 
-            module TestModule {
-                func test() -> A {
-                    @return a
+    #[test]
+    #[should_panic]
+    fn generic_member_access() {
+        let program = "
+            unit test;
 
-                    @spawn a: A {
-                        let b = AModule.new();
-                        return b;
+            data User {
+                id: i32
+            }
+
+            module M {
+                func first(users: List<User>) -> User {
+                    @return user
+
+                    @spawn user: User {
+                        use users;
+                        let user = copy(users.0);
+                        return user;
                     }
                 }
+
+                func copy(user: User) -> User {
+                    @return user
+                }
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn ref_function_using_completed_name_2() {
+    fn generic_data_and_module() {
         let program = "
             unit test;
 
-            data A {}
+            data User {
+                id: i32
+            }
 
-            module TestModule {
-                func new() -> A {
-                    @return a
+            data Page<T> {
+                items: List<T>,
+                first: T
+            }
 
-                    @spawn a: A
+            module Repository<T> {
+                func save(item: T) -> T {
+                    @return item
                 }
 
-                func test() -> A {
-                    @return a
+                func all(page: Page<T>) -> List<T> {
+                    @return items
 
-                    @spawn a: A {
-                        let b = TestModule.new();
-                        return b;
+                    @spawn items: List<T> {
+                        use page;
+                        let items = copy(page.items);
+                        return items;
+                    }
+                }
+
+                func copy(items: List<T>) -> List<T> {
+                    @return items
+                }
+            }
+
+            module UserModule {
+                func listup(page: Page<User>) -> List<User> {
+                    @return users
+
+                    @spawn users: List<User> {
+                        use page;
+                        let users = Repository<User>.all(page);
+                        return users;
+                    }
+                }
+
+                func first(page: Page<User>) -> User {
+                    @return user
+
+                    @spawn user: User {
+                        use page;
+                        let user = Repository<User>.save(page.first);
+                        return user;
                     }
                 }
             }
         ";
-        check(vec![program]);
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let unit = &system.units[0];
+        let (_, first) = &unit.data[1].members[1];
+        assert_eq!(first.kind, TypeKind::Param);
+        assert_eq!(
+            first.refs.as_ref().unwrap().get_full_name(),
+            ".0.test.Page.T"
+        );
+
+        let listup = &unit.modules[1].functions[0];
+        let page = &listup.args[0].1;
+        assert_eq!(page.kind, TypeKind::Data);
+        assert_eq!(page.params[0].refs.as_ref().unwrap().name, "User");
+        match &listup.annotations[0] {
+            SysDCAnnotation::Spawn { details, .. } => match &details[1] {
+                SysDCSpawnDetail::LetTo {
+                    func, type_args, ..
+                } => {
+                    assert_eq!(func.1.kind, TypeKind::List);
+                    assert_eq!(func.1.params[0].refs.as_ref().unwrap().name, "User");
+                    assert_eq!(type_args[0].refs.as_ref().unwrap().name, "User");
+                }
+                _ => panic!(),
+            },
+            _ => panic!(),
+        }
     }
 
     #[test]
-    fn ref_function_using_uncompleted_name() {
+    #[should_panic]
+    fn generic_module_arg_unmatch() {
         let program = "
             unit test;
 
-            data A {}
+            data User {
+                id: i32
+            }
 
-            module TestModule {
-                func new() -> A {
-                    @return a
+            data Box {
+                x: i32
+            }
 
-                    @spawn a: A
+            module Repository<T> {
+                func save(item: T) -> T {
+                    @return item
                 }
+            }
 
-                func test() -> A {
-                    @return a
-
-                    @spawn a: A {
-                        let b = new();
-                        return b;
-                    }
+            module M {
+                proc f(box: Box) {
+                    @affect Repository<User>.save(box)
                 }
             }
         ";
@@ -503,26 +697,23 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn ref_function_using_completed_name_failure() {
+    fn generic_module_without_type_args() {
         let program = "
             unit test;
 
-            data A {}
-
-            module TestModule {
-                func new() -> A {
-                    @return a
+            data User {
+                id: i32
+            }
 
-                    @spawn a: A
+            module Repository<T> {
+                func save(item: T) -> T {
+                    @return item
                 }
+            }
 
-                func test() -> A {
-                    @return a
-
-                    @spawn a: A {
-                        let b = TestModule.new2();
-                        return b;
-                    }
+            module M {
+                proc f(user: User) {
+                    @affect Repository.save(user)
                 }
             }
         ";
@@ -531,71 +722,112 @@ mod test {
 
     #[test]
     #[should_panic]
-    fn ref_function_using_uncompleted_name_failure() {
+    fn generic_data_params_length_not_match() {
         let program = "
             unit test;
 
-            data A {}
+            data Page<T> {
+                first: T
+            }
 
-            module TestModule {
-                func new() -> A {
-                    @return a
+            data Book {
+                pages: Page<i32, i32>
+            }
+        ";
+        check(vec![program]);
+    }
 
-                    @spawn a: A
-                }
+    #[test]
+    #[should_panic]
+    fn type_param_member_access() {
+        let program = "
+            unit test;
 
-                func test() -> A {
-                    @return a
+            module Repository<T> {
+                func id(item: T) -> i32 {
+                    @return id
 
-                    @spawn a: A {
-                        let b = new2();
-                        return b;
+                    @spawn id: i32 {
+                        use item;
+                        let id = get(item.id);
+                        return id;
                     }
                 }
+
+                func get(id: i32) -> i32 {
+                    @return id
+                }
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn function_argument_check_ok() {
+    fn doc_comments() {
         let program = "
+            %% テスト用のUnit %%
             unit test;
 
-            data A {}
-            data B {}
-            data C {}
-
-            module TestModule {
-                func test() -> A {
-                    @return a
+            %% 箱 %%
+            data Box {
+                %% 横幅 %%
+                x: i32
+            }
 
-                    @spawn a: A {
-                        let tmp1 = genA();
-                        let tmp2 = genB(tmp1);
-                        let tmp3 = genC(tmp1, tmp2);
-                    }
+            %% 箱を扱うModule %%
+            module BoxModule {
+                %% 箱を動かす %%
+                proc move(
+                    %% 動かす箱 %%
+                    box: Box
+                ) {
+                    @modify box
                 }
+            }
+        ";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let doc = |name: &Name| name.doc.clone().unwrap_or_default();
+        let unit = &system.units[0];
+        assert_eq!(doc(&unit.name), "テスト用のUnit");
+        assert_eq!(doc(&unit.data[0].name), "箱");
+        assert_eq!(doc(&unit.data[0].members[0].0), "横幅");
+        assert_eq!(doc(&unit.modules[0].name), "箱を扱うModule");
+
+        let func = &unit.modules[0].functions[0];
+        assert_eq!(doc(&func.name), "箱を動かす");
+        assert_eq!(doc(&func.args[0].0), "動かす箱");
+        assert_eq!(func.args[0].1.refs.as_ref().unwrap().doc, None);
+        match &func.annotations[0] {
+            SysDCAnnotation::Modify { target, .. } => assert_eq!(target.0.doc, None),
+            _ => panic!(),
+        }
+    }
 
-                func genA() -> A {
-                    @return a
+    #[test]
+    fn unicode_identifiers() {
+        let program = "
+            unit テスト.図形;
 
-                    @spawn a: A
-                }
+            data 箱 {
+                横幅: i32,
+                高さ: i32
+            }
 
-                func genB(a: A) -> B {
-                    @return b
+            module 箱モジュール {
+                func 生成(横幅: i32, 高さ: i32) -> 箱 {
+                    @return 結果
 
-                    @spawn b: B {
-                        use a;
+                    @spawn 結果: 箱 {
+                        use 横幅, 高さ;
                     }
                 }
 
-                func genC(a: A, b: B) -> C {
-                    @return c
-
-                    @spawn c: C {
-                        use a, b;
+                proc 移動(対象: 箱, 距離: i32) {
+                    @modify 対象 {
+                        use 距離;
                     }
                 }
             }
@@ -604,231 +836,1864 @@ mod test {
     }
 
     #[test]
-    #[should_panic]
-    fn function_argument_check_ng_1() {
+    fn data_composition() {
         let program = "
             unit test;
 
-            data A {}
-            data B {}
-            data C {}
-
-            module TestModule {
-                func test() -> A {
-                    @return a
+            data Permissions {
+                level: i32
+            }
 
-                    @spawn a: A {
-                        let tmp1 = genA();
-                        let tmp2 = genB(tmp1);
-                        let tmp3 = genC(tmp1, tmp1);
-                    }
-                }
+            data User {
+                id: i32
+            }
 
-                func genA() -> A {
-                    @return a
+            data Page<T> {
+                first: T
+            }
 
-                    @spawn a: A
-                }
+            data AdminUser = User + Page<Permissions> + {
+                permissions: Permissions
+            }
 
-                func genB(a: A) -> B {
-                    @return b
+            module AdminModule {
+                func adminLevel(admin: AdminUser) -> i32 {
+                    @return level
 
-                    @spawn b: B {
-                        use a;
+                    @spawn level: i32 {
+                        use admin;
+                        let level = get(admin.first.level);
+                        return level;
                     }
                 }
 
-                func genC(a: A, b: B) -> C {
-                    @return c
+                func adminId(admin: AdminUser) -> i32 {
+                    @return id
 
-                    @spawn c: C {
-                        use a, b;
+                    @spawn id: i32 {
+                        use admin;
+                        let id = get(admin.id);
+                        return id;
                     }
                 }
+
+                func get(x: i32) -> i32 {
+                    @return x
+                }
             }
         ";
-        check(vec![program]);
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let admin = &system.units[0].data[3];
+        assert_eq!(admin.extends.len(), 2);
+        assert_eq!(admin.extends[0].refs.as_ref().unwrap().name, "User");
+        assert_eq!(
+            admin.extends[1].params[0].refs.as_ref().unwrap().name,
+            "Permissions"
+        );
+        assert_eq!(admin.members.len(), 1);
     }
 
     #[test]
     #[should_panic]
-    fn function_argument_check_ng_2() {
+    fn data_composition_has_duplicate_member() {
         let program = "
             unit test;
 
-            module TestModule {
-                func test(a: i32) -> i32 {
-                    @return result
-
-                    @spawn result: i32 {
-                        use a;
-                        let val = test2(a);
-                        return val;
-                    }
-                }
+            data User {
+                id: i32
+            }
 
-                func test2(a: i32, b: i32) -> i32 {
-                    @return result
-                    @spawn result: i32
-                }
+            data AdminUser = User + {
+                id: i32
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn function_return_check_ok() {
+    #[should_panic]
+    fn data_composition_bases_have_duplicate_member() {
         let program = "
             unit test;
 
-            module TestModule {
-                func test() -> i32 {
-                    @return a
+            data User {
+                id: i32
+            }
 
-                    @spawn a: i32
-                }
+            data Group {
+                id: i32
             }
+
+            data Member = User + Group
         ";
         check(vec![program]);
     }
 
     #[test]
     #[should_panic]
-    fn function_return_check_ng() {
+    fn data_composed_from_non_data() {
         let program = "
             unit test;
 
-            data A {}
-
-            module TestModule {
-                func test() -> i32 {
-                    @return a
+            module Users {}
 
-                    @spawn a: A
-                }
+            data AdminUser = Users + {
+                id: i32
             }
         ";
         check(vec![program]);
     }
 
     #[test]
-    fn in_spawn_return_check_ok() {
+    fn interface_and_implements() {
         let program = "
             unit test;
 
-            module TestModule {
-                func test() -> i32 {
-                    @return a
+            data Blob {
+                id: i32
+            }
 
-                    @spawn a: i32 {
-                        let b = gen_i32();
-                        return b;
-                    }
+            interface Storage {
+                proc save(x: Blob)
+                func load(id: i32) -> Blob
+            }
+
+            module FileStorage implements Storage {
+                pub proc save(blob: Blob) {
+                    @modify blob
                 }
 
-                func gen_i32() -> i32 {
-                    @return a
+                pub func load(id: i32) -> Blob {
+                    @return blob
 
-                    @spawn a: i32
+                    @spawn blob: Blob
                 }
             }
-        ";
-        check(vec![program]);
-    }
 
-    #[test]
-    #[should_panic]
-    fn in_spawn_return_check_ng() {
+            module Service {
+                proc store(blob: Blob) {
+                    @affect Storage.save(blob)
+                }
+
+                func restore(id: i32) -> Blob {
+                    @return blob
+
+                    @spawn blob: Blob {
+                        use id;
+                        let blob = Storage.load(id);
+                        return blob;
+                    }
+                }
+            }
+        ";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let unit = &system.units[0];
+        assert_eq!(unit.interfaces[0].functions.len(), 2);
+        assert_eq!(
+            unit.modules[0].implements[0].get_full_name(),
+            ".0.test.Storage"
+        );
+        match &unit.modules[1].functions[0].annotations[0] {
+            SysDCAnnotation::Affect { func, .. } => {
+                assert_eq!(func.0.get_full_name(), ".0.test.Storage.save")
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn implements_imported_interface() {
+        let program1 = "
+            unit test;
+
+            from test2 import Blob, Storage;
+
+            module FileStorage implements Storage {
+                pub proc save(x: Blob) {
+                    @modify x
+                }
+            }
+        ";
+        let program2 = "
+            unit test2;
+
+            pub data Blob {
+                id: i32
+            }
+
+            pub interface Storage {
+                proc save(x: Blob)
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interface_func_not_implemented() {
         let program = "
             unit test;
 
-            data A {}
+            data Blob {
+                id: i32
+            }
+
+            interface Storage {
+                proc save(x: Blob)
+                func load(id: i32) -> Blob
+            }
+
+            module FileStorage implements Storage {
+                pub proc save(x: Blob) {
+                    @modify x
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interface_signature_unmatch() {
+        let program = "
+            unit test;
+
+            data Blob {
+                id: i32
+            }
+
+            interface Storage {
+                func load(id: i32) -> Blob
+            }
+
+            module FileStorage implements Storage {
+                pub func load(id: i32) -> i32 {
+                    @return id
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn interface_kind_unmatch() {
+        let program = "
+            unit test;
+
+            interface Clock {
+                proc tick(id: i32)
+            }
+
+            module SystemClock implements Clock {
+                pub func tick(id: i32) -> void {
+                    @return done
+                    @spawn done: void
+                }
+            }
+        ";
+        assert_error_at(vec![program], "check.def:9:26");
+    }
+
+    #[test]
+    fn interface_func_not_public() {
+        let program = "
+            unit test;
+
+            interface Clock {
+                func now(id: i32) -> i32
+            }
+
+            module SystemClock implements Clock {
+                func now(id: i32) -> i32 {
+                    @return id
+                }
+            }
+        ";
+        assert_error_at(vec![program], "check.def:9:22");
+    }
+
+    #[test]
+    #[should_panic]
+    fn implements_not_interface() {
+        let program = "
+            unit test;
+
+            data Blob {
+                id: i32
+            }
+
+            module FileStorage implements Blob {
+                proc save(x: Blob) {
+                    @modify x
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interface_arg_unmatch() {
+        let program = "
+            unit test;
+
+            data Blob {
+                id: i32
+            }
+
+            interface Storage {
+                proc save(x: Blob)
+            }
+
+            module Service {
+                proc store(id: i32) {
+                    @affect Storage.save(id)
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_simple() {
+        let program = "
+            unit test;
+
+            data A {
+                a: i32,
+                b: i32
+            }
 
             module TestModule {
-                func test() -> i32 {
+                func test(a: A) -> A {
                     @return a
+                }
+            }
+        ";
+        check(vec![program]);
+    }
 
-                    @spawn a: i32 {
-                        let b = gen_A();
-                        return b;
+    #[test]
+    fn module_with_affect_1() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {}
+
+            module TestModuleA {
+                proc test(a: A, b: B) {}
+            }
+
+            module TestModuleB {
+                proc test(a: A, b: B) {
+                    @affect TestModuleA.test(a, b)
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_with_affect_2() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {}
+
+            module TestModuleA {
+                proc test(a: A, b: B) {
+                    @affect test2(a, b)
+                }
+
+                proc test2(a: A, b: B) {}
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_affect_failure_1() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {}
+
+            module TestModuleA {
+                proc test(a: A, b: B) {
+                    @affect test2(a)
+                }
+
+                proc test2(a: A, b: B) {}
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_with_modify_1() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {}
+
+            data C {}
+
+            module TestModule {
+                proc test(a: A, b: B, c: C) {
+                    @modify a {
+                        use b, c;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_with_modify_2() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {}
+
+            data C {}
+
+            module TestModule {
+                proc test(a: A, b: B, c: C) {
+                    @modify a
+                    @modify b
+                    @modify c
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_failure_1() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {
+                a: i32
+            }
+
+            module TestModule {
+                proc test(a: A, b: B, c: C) {
+                    @modify a {
+                        use b.a;
                     }
                 }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_failure_2() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B {
+                a: i32
+            }
+
+            module TestModule {
+                proc test(a: A, b: B, c: C) {
+                    @modify a {
+                        use b, c;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_with_modify_spawned_variable() {
+        let program = "
+            unit test;
+
+            data A {
+                x: i32
+            }
+
+            module TestModule {
+                proc test(x: i32) {
+                    @modify a {
+                        use x;
+                    }
+
+                    @spawn a: A {
+                        use x;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_primitive() {
+        let program = "
+            unit test;
+
+            module TestModule {
+                proc test(a: i32, b: i32) {
+                    @modify a {
+                        use b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_enum() {
+        let program = "
+            unit test;
+
+            enum Status {
+                Active,
+                Inactive
+            }
+
+            module TestModule {
+                proc test(status: Status) {
+                    @modify status
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_collection() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                proc test(a: List<A>) {
+                    @modify a
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_undefined_use() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                proc test(a: A) {
+                    @modify a {
+                        use b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_use_not_variable() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                proc test(a: A) {
+                    @modify a {
+                        use A;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_modify_use_in_spawn() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func make(x: i32) -> A {
+                    @return made
+
+                    @spawn made: A {
+                        use x;
+                    }
+                }
+
+                proc test(a: A, x: i32) {
+                    @modify a {
+                        use y;
+                    }
+
+                    @spawn b: A {
+                        let y = make(x);
+                        return y;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_with_spawn() {
+        let program = "
+            unit test;
+
+            data A {
+                a: B,
+                b: B
+            }
+
+            data B {
+                a: C,
+                b: C
+            }
+
+            data C {
+                a: i32,
+                b: i32
+            }
+
+            module TestModule {
+                func test(a: A) -> A {
+                    @return b
+
+                    @spawn b: A {
+                        use a;
+                        let tmp = receiveA(a);
+                        let tmp1 = receiveB(a.a);
+                        let tmp2 = receiveB(a.b);
+                        let tmp3 = receiveC(a.a.a);
+                        let tmp4 = receiveC(a.a.b);
+                        let tmp5 = receiveC(a.b.a);
+                        let tmp6 = receiveC(a.b.b);
+                        let tmp7 = receiveInt32(a.a.a.a);
+                        let tmp8 = receiveInt32(a.a.a.b);
+                        let tmp9 = receiveInt32(a.a.b.a);
+                        let tmp10 = receiveInt32(a.a.b.b);
+                        let tmp11 = receiveInt32(a.b.a.a);
+                        let tmp12 = receiveInt32(a.b.a.b);
+                        let tmp13 = receiveInt32(a.b.b.a);
+                        let tmp14 = receiveInt32(a.b.b.b);
+                    }
+                }
+
+                func receiveA(a: A) -> i32 {
+                    @return tmp
+                    @spawn tmp: i32
+                }
+
+                func receiveB(b: B) -> i32 {
+                    @return tmp
+                    @spawn tmp: i32
+                }
+
+                func receiveC(c: C) -> i32 {
+                    @return tmp
+                    @spawn tmp: i32
+                }
+
+                func receiveInt32(i: i32) -> i32 {
+                    @return tmp
+                    @spawn tmp: i32
+                }
+            }
+        ";
+        check(vec![program])
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_spawn_failure_1() {
+        let program = "
+            unit test;
+
+            data A {
+                a: i32,
+                b: i32
+            }
+
+            module TestModule {
+                func test(a: A) -> A {
+                    @return b
+
+                    @spawn b: A {
+                        use aaa;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_spawn_failure_2() {
+        let program = "
+            unit test;
+
+            data A {
+                a: i32,
+                b: i32
+            }
+
+            module TestModule {
+                func test(a: A) -> A {
+                    @return b
+
+                    @spawn b: A {
+                        use a;
+                        let tmp = receiveInt32(a.c);
+                    }
+                }
+
+                func receiveInt32(i: i32) -> i32 {
+                    @return tmp
+                    @spawn tmp: i32
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn module_with_spawn_failure_3() {
+        let program = "
+            unit test;
+
+            data A {
+                a: B,
+                b: B
+            }
+
+            data B {
+                a: C,
+                b: C
+            }
+
+            data C {
+                a: i32,
+                b: i32
+            }
+
+            module TestModule {
+                func test(a: A) -> A {
+                    @return b
+
+                    @spawn b: A {
+                        use a.b.a.c;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn module_with_spawn_failure_4() {
+        let program = "
+            unit test;
+
+            module TestModule {
+                func test(a: i32) -> i32 {
+                    @return b
+
+                    @spawn b: i32 {
+                        let tmp = i32(a);
+                        return tmp;
+                    }
+                }
+            }
+        ";
+        assert_error_at(vec![program], "check.def:9:35");
+    }
+
+    #[test]
+    fn module_with_spawn_failure_5() {
+        for callee in ["List", "Map", "Option"] {
+            let program = format!(
+                "
+                unit test;
+
+                module TestModule {{
+                    func test(a: i32) -> i32 {{
+                        @return b
+
+                        @spawn b: i32 {{
+                            let tmp = {}(a);
+                            return tmp;
+                        }}
+                    }}
+                }}
+            ",
+                callee
+            );
+            assert_error_at(vec![&program], "check.def:9:39");
+        }
+    }
+
+    #[test]
+    fn module_with_spawn_failure_6() {
+        for callee in ["string", "u64", "bytes", "any"] {
+            let program = format!(
+                "
+                unit test;
+
+                module TestModule {{
+                    func test(x: string) -> string {{
+                        @return v
+
+                        @spawn v: string {{
+                            let tmp = {}(x);
+                            return tmp;
+                        }}
+                    }}
+                }}
+            ",
+                callee
+            );
+            assert_error_at(vec![&program], "check.def:9:39");
+        }
+    }
+
+    #[test]
+    fn ref_function_using_completed_name_1() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module AModule {
+                func new() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+            }
+
+            module TestModule {
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let b = AModule.new();
+                        return b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn ref_function_using_completed_name_2() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func new() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let b = TestModule.new();
+                        return b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn ref_function_using_uncompleted_name() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func new() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let b = new();
+                        return b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ref_function_using_completed_name_failure() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func new() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let b = TestModule.new2();
+                        return b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ref_function_using_uncompleted_name_failure() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func new() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let b = new2();
+                        return b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn function_argument_check_ok() {
+        let program = "
+            unit test;
+
+            data A {}
+            data B {}
+            data C {}
+
+            module TestModule {
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let tmp1 = genA();
+                        let tmp2 = genB(tmp1);
+                        let tmp3 = genC(tmp1, tmp2);
+                    }
+                }
+
+                func genA() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+
+                func genB(a: A) -> B {
+                    @return b
+
+                    @spawn b: B {
+                        use a;
+                    }
+                }
+
+                func genC(a: A, b: B) -> C {
+                    @return c
+
+                    @spawn c: C {
+                        use a, b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn function_argument_check_ng_1() {
+        let program = "
+            unit test;
+
+            data A {}
+            data B {}
+            data C {}
+
+            module TestModule {
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let tmp1 = genA();
+                        let tmp2 = genB(tmp1);
+                        let tmp3 = genC(tmp1, tmp1);
+                    }
+                }
+
+                func genA() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+
+                func genB(a: A) -> B {
+                    @return b
+
+                    @spawn b: B {
+                        use a;
+                    }
+                }
+
+                func genC(a: A, b: B) -> C {
+                    @return c
+
+                    @spawn c: C {
+                        use a, b;
+                    }
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn function_argument_check_ng_2() {
+        let program = "
+            unit test;
+
+            module TestModule {
+                func test(a: i32) -> i32 {
+                    @return result
+
+                    @spawn result: i32 {
+                        use a;
+                        let val = test2(a);
+                        return val;
+                    }
+                }
+
+                func test2(a: i32, b: i32) -> i32 {
+                    @return result
+                    @spawn result: i32
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn function_return_check_ok() {
+        let program = "
+            unit test;
+
+            module TestModule {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn function_return_check_ng() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: A
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn in_spawn_return_check_ok() {
+        let program = "
+            unit test;
+
+            module TestModule {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32 {
+                        let b = gen_i32();
+                        return b;
+                    }
+                }
+
+                func gen_i32() -> i32 {
+                    @return a
+
+                    @spawn a: i32
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn in_spawn_return_check_ng() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32 {
+                        let b = gen_A();
+                        return b;
+                    }
+                }
+
+                func gen_A() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn procedure_simple() {
+        let program = "
+            unit test;
+
+            module TestModule {
+                proc test() { }
+            }
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn import_data_in_other_unit_simple() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A;
+
+            data B {
+                a: A
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    fn import_data_in_other_unit_recursive() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {
+                b: B
+            }
+
+            data B {
+                c: C
+            }
+
+            data C {
+                body: i32
+            }
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A;
+
+            module TestModule {
+                func new() -> A {
+                    @return a
+                    @spawn a: A
+                }
+
+                func test() -> i32 {
+                    @return v
+
+                    @spawn v: i32 {
+                        let a = new();
+                        return a.b.c.body;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    fn import_module_in_other_unit() {
+        let program1 = "
+            unit test.A;
+
+            pub module TestModule {
+                pub func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32
+                }
+            }
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import TestModule;
+
+            module TestModule2 {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32 {
+                        let a = TestModule.test();
+                        return a;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    fn visibility_of_items_in_other_unit() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+
+            pub(test) module TestModule {
+                pub func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let a = helper();
+                        return a;
+                    }
+                }
+
+                func helper() -> A {
+                    @return a
+                    @spawn a: A
+                }
+            }
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A, TestModule;
+
+            module TestModule2 {
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let a = TestModule.test();
+                        return a;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_private_data() {
+        let program1 = "
+            unit test.A;
+
+            data A {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A;
+
+            data B {
+                a: A
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn call_private_function_in_other_unit() {
+        let program1 = "
+            unit test.A;
+
+            pub module TestModule {
+                func test() -> i32 {
+                    @return a
+                    @spawn a: i32
+                }
+            }
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import TestModule;
+
+            module TestModule2 {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32 {
+                        let a = TestModule.test();
+                        return a;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_module_in_other_unit_failure() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+
+            pub module TestModule {
+                pub func test() -> A {
+                    @return a
+
+                    @spawn a: A
+                }
+            }
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A, TestModule;
+
+            module TestModule2 {
+                func test() -> i32 {
+                    @return a
+
+                    @spawn a: i32 {
+                        let a = TestModule.test();
+                        return a;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_restricted_module_out_of_scope() {
+        let program1 = "
+            unit test.A;
+
+            pub(test) module TestModule {
+                pub proc test() {}
+            }
+        ";
+        let program2 = "
+            unit other;
+
+            from test.A import TestModule;
+
+            module TestModule2 {
+                proc test() {
+                    @affect TestModule.test()
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    // ※親のUnitの非公開の定義も，子のUnitからは参照できない
+    #[test]
+    fn refer_private_data_in_parent_unit() {
+        let program1 = "
+            unit test;
+
+            data A {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            data B {
+                a: A
+            }
+        ";
+        assert_error_at(vec![program1, program2], "check.def:5:17");
+        assert_label_at(vec![program1, program2], "check.def:4:18", "defined here");
+    }
+
+    #[test]
+    fn import_with_alias() {
+        let program1 = "
+            unit test.A;
+
+            pub data Error {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            pub data Error {}
+        ";
+        let program3 = "
+            unit test.C;
+
+            from test.A import Error as AError;
+            from test.B import Error as BError;
+
+            data C {
+                a: AError,
+                b: BError
+            }
+        ";
+        check(vec![program1, program2, program3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn refer_original_name_of_aliased_import() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A as AliasA;
+
+            data B {
+                a: A
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn duplicate_import_alias() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+            pub data B {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A as X, B as X;
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    fn import_wildcard() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+
+            pub module TestModule {
+                pub func test() -> A {
+                    @return a
+                    @spawn a: A
+                }
+            }
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import *;
+
+            module TestModule2 {
+                func test() -> A {
+                    @return a
+
+                    @spawn a: A {
+                        let a = TestModule.test();
+                        return a;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_wildcard_private_data() {
+        let program1 = "
+            unit test.A;
+
+            data A {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import *;
+
+            data B {
+                a: A
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_wildcard_from_unknown_unit() {
+        let program = "
+            unit test.B;
+
+            from test.A import *;
+        ";
+        check(vec![program]);
+    }
+
+    #[test]
+    fn import_unit() {
+        let program1 = "
+            unit std.time;
+
+            pub data Timestamp {}
+
+            pub module Time {
+                pub func get_now_time() -> Timestamp {
+                    @return now
+                    @spawn now: Timestamp
+                }
+            }
+        ";
+        let program2 = "
+            unit test;
+
+            import std.time;
+            import std.time as clock;
+
+            data Log {
+                at: time.Timestamp
+            }
+
+            module Logger {
+                func timestamp() -> clock.Timestamp {
+                    @return now
+
+                    @spawn now: time.Timestamp {
+                        let now = time.Time.get_now_time();
+                        return now;
+                    }
+                }
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    fn member_has_same_name_as_imported_unit() {
+        let program1 = "
+            unit std.time;
+
+            pub data Timestamp {}
+        ";
+        let program2 = "
+            unit test;
+
+            import std.time;
+
+            data Log {
+                time: time.Timestamp
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    fn import_parent_unit() {
+        let program1 = "
+            unit std;
+        ";
+        let program2 = "
+            unit std.time;
+
+            pub data Timestamp {}
+        ";
+        let program3 = "
+            unit test;
+
+            import std;
+
+            data Log {
+                at: std.time.Timestamp
+            }
+        ";
+        check(vec![program1, program2, program3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn refer_unit_as_type() {
+        let program1 = "
+            unit std.time;
+
+            pub data Timestamp {}
+        ";
+        let program2 = "
+            unit test;
+
+            import std.time;
+
+            data Log {
+                at: time
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn refer_private_data_via_unit() {
+        let program1 = "
+            unit std.time;
+
+            data Timestamp {}
+        ";
+        let program2 = "
+            unit test;
+
+            import std.time;
+
+            data Log {
+                at: time.Timestamp
+            }
+        ";
+        check(vec![program1, program2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_is_local_to_unit() {
+        let program1 = "
+            unit test.A;
+
+            pub data A {}
+        ";
+        let program2 = "
+            unit test.B;
+
+            from test.A import A;
+        ";
+        let program3 = "
+            unit test.C;
+
+            data C {
+                a: A
+            }
+        ";
+        check(vec![program1, program2, program3]);
+    }
+
+    #[test]
+    fn rule_allows_dependency() {
+        let program1 = "
+            unit domain;
+
+            rule {
+                deny domain.* -> infra.*;
+            }
+
+            pub data User {}
+        ";
+        let program2 = "
+            unit infra;
+
+            from domain import User;
+
+            pub module Database {
+                pub proc save(user: User) {}
+            }
+        ";
+        let program3 = "
+            unit app;
 
-                func gen_A() -> A {
-                    @return a
+            from domain import User;
+            from infra import Database;
 
-                    @spawn a: A
+            module UserService {
+                proc register(user: User) {
+                    @affect Database.save(user)
                 }
             }
         ";
-        check(vec![program]);
+        check(vec![program1, program2, program3]);
     }
 
     #[test]
-    fn procedure_simple() {
-        let program = "
-            unit test;
+    #[should_panic]
+    fn rule_denies_import() {
+        let program1 = "
+            unit domain.user;
 
-            module TestModule {
-                proc test() { }
+            from infra.db import Database;
+
+            rule {
+                deny domain.* -> infra.*;
             }
         ";
-        check(vec![program]);
+        let program2 = "
+            unit infra.db;
+
+            pub data Database {}
+        ";
+        check(vec![program1, program2]);
     }
 
     #[test]
-    fn import_data_in_other_unit_simple() {
+    #[should_panic]
+    fn rule_denies_unit_import() {
         let program1 = "
-            unit test.A;
+            unit domain;
 
-            data A {}
+            import infra;
         ";
         let program2 = "
-            unit test.B;
+            unit infra;
 
-            from test.A import A;
-
-            data B {
-                a: A
+            rule {
+                deny domain -> infra;
             }
         ";
         check(vec![program1, program2]);
     }
 
     #[test]
-    fn import_data_in_other_unit_recursive() {
+    #[should_panic]
+    fn rule_denies_call_from_sub_unit() {
         let program1 = "
-            unit test.A;
-
-            data A {
-                b: B
-            }
+            unit domain;
 
-            data B {
-                c: C
+            rule {
+                deny domain.user -> domain;
             }
 
-            data C {
-                body: i32
+            pub module Clock {
+                pub func now() -> i32 {
+                    @return t
+                    @spawn t: i32
+                }
             }
         ";
         let program2 = "
-            unit test.B;
-
-            from test.A import A;
-
-            module TestModule {
-                func new() -> A {
-                    @return a
-                    @spawn a: A
-                }
+            unit domain.user;
 
-                func test() -> i32 {
-                    @return v
+            module UserService {
+                func created() -> i32 {
+                    @return at
 
-                    @spawn v: i32 {
-                        let a = new();
-                        return a.b.c.body;
+                    @spawn at: i32 {
+                        let at = Clock.now();
+                        return at;
                     }
                 }
             }
@@ -837,70 +2702,76 @@ mod test {
     }
 
     #[test]
-    fn import_module_in_other_unit() {
+    fn rule_denies_type_from_parent_unit() {
         let program1 = "
-            unit test.A;
+            unit domain;
 
-            module TestModule {
-                func test() -> i32 {
-                    @return a
-
-                    @spawn a: i32
-                }
+            rule {
+                deny domain.user -> domain;
             }
+
+            pub data Id {}
         ";
         let program2 = "
-            unit test.B;
+            unit domain.user;
 
-            from test.A import TestModule;
-
-            module TestModule2 {
-                func test() -> i32 {
-                    @return a
-
-                    @spawn a: i32 {
-                        let a = TestModule.test();
-                        return a;
-                    }
-                }
+            data User {
+                id: Id
             }
         ";
-        check(vec![program1, program2]);
+        assert_error_at(vec![program1, program2], "check.def:5:17");
+        assert_label_at(
+            vec![program1, program2],
+            "check.def:5:17",
+            "denied by this rule",
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn import_module_in_other_unit_failure() {
+    fn rule_denies_type_arg_from_parent_unit() {
         let program1 = "
-            unit test.A;
+            unit domain;
 
-            data A {}
+            rule {
+                deny domain.user -> domain;
+            }
 
-            module TestModule {
-                func test() -> A {
-                    @return a
+            pub data Id {}
+        ";
+        let program2 = "
+            unit domain.user;
 
-                    @spawn a: A
-                }
+            module UserService {
+                proc remove(ids: List<Id>) {}
+            }
+        ";
+        assert_error_at(vec![program1, program2], "check.def:5:29");
+    }
+
+    #[test]
+    fn rule_pattern_without_sub_units() {
+        let program1 = "
+            unit infra;
+
+            rule {
+                deny infra -> domain;
             }
         ";
         let program2 = "
-            unit test.B;
+            unit domain;
 
-            from test.A import A, TestModule;
+            pub data User {}
+        ";
+        let program3 = "
+            unit infra.db;
 
-            module TestModule2 {
-                func test() -> i32 {
-                    @return a
+            from domain import User;
 
-                    @spawn a: i32 {
-                        let a = TestModule.test();
-                        return a;
-                    }
-                }
+            data Record {
+                user: User
             }
         ";
-        check(vec![program1, program2]);
+        check(vec![program1, program2, program3]);
     }
 
     #[test]
@@ -1048,12 +2919,413 @@ mod test {
         check(vec![program]);
     }
 
+    #[test]
+    fn location_is_kept_after_check() {
+        let program = "unit test;
+
+module TestModule {
+    proc test(a: i32) {
+        @affect test2(a)
+    }
+
+    proc test2(a: i32) {}
+}";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let system = parser.check().unwrap();
+
+        let func = &system.units[0].modules[0].functions[0];
+        assert_eq!(func.loc.begin, Some((4, 5)));
+        match &func.annotations[0] {
+            SysDCAnnotation::Affect { func, args, .. } => {
+                assert_eq!(func.0.name, "test2");
+                assert_eq!(func.0.loc.begin, Some((5, 17)));
+                assert_eq!(args[0].0.loc.begin, Some((5, 23)));
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn error_location_not_found() {
+        let program = "unit test;
+
+data Test {
+    a: i32,
+    b: Unknown
+}";
+        assert_error_at(vec![program], "check.def:5:5");
+    }
+
+    #[test]
+    fn error_location_already_defined() {
+        let program = "unit test;
+
+data A {}
+data A {}";
+        assert_error_at(vec![program], "check.def:4:6");
+        assert_label_at(vec![program], "check.def:3:6", "first defined here");
+    }
+
+    #[test]
+    fn error_location_private_item() {
+        let program1 = "unit test;
+
+data A {}";
+        let program2 = "unit test2;
+
+from test import A;";
+        assert_error_at(vec![program1, program2], "check.def:3:18");
+        assert_label_at(vec![program1, program2], "check.def:3:6", "defined here");
+    }
+
+    #[test]
+    fn error_location_forbidden_dependency() {
+        let program1 = "unit domain;
+
+rule {
+    deny domain.user -> domain;
+}
+
+pub module Clock {
+    pub proc tick() {}
+}";
+        let program2 = "unit domain.user;
+
+module UserService {
+    proc register() {
+        @affect Clock.tick()
+    }
+}";
+        assert_error_at(vec![program1, program2], "check.def:5:17");
+        assert_label_at(
+            vec![program1, program2],
+            "check.def:4:5",
+            "denied by this rule",
+        );
+    }
+
+    #[test]
+    fn error_location_recursive_data() {
+        let program = "unit test;
+
+data A {
+    b: B
+}
+
+data B {
+    x: i32,
+    a: A
+}";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let err = parser.check().unwrap_err();
+        assert!(format!("{}", err).contains("A.b -> B.a -> A"), "{}", err);
+        assert_error_at(vec![program], "check.def:4:5");
+        assert_label_at(vec![program], "check.def:9:5", "\"B.a\" is declared here");
+    }
+
+    #[test]
+    fn error_location_modify_target_not_data() {
+        let program = "unit test;
+
+module TestModule {
+    proc test(a: i32) {
+        @modify a
+    }
+}";
+        assert_error_at(vec![program], "check.def:5:17");
+    }
+
+    #[test]
+    fn error_message_type_unmatch() {
+        let program = "unit test;
+
+data User {}
+
+module TestModule {
+    proc test(users: List<User>) {
+        @affect test2(users)
+    }
+
+    proc test2(users: Map<string, List<User>>) {}
+}";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let err = parser.check().unwrap_err().downcast::<PError>().unwrap();
+        assert_eq!(
+            format!("{}", err.kind()),
+            "\"Map<string, List<User>>\" is required, but \"List<User>\" found"
+        );
+    }
+
+    #[test]
+    fn error_location_recursive_data_through_type_param() {
+        let program = "unit test;
+
+data Page<T> {
+    item: T
+}
+
+data A {
+    p: Page<A>
+}";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let err = parser.check().unwrap_err();
+        assert!(
+            format!("{}", err).contains("A.p -> Page.item -> A"),
+            "{}",
+            err
+        );
+        assert_error_at(vec![program], "check.def:8:5");
+        assert_label_at(
+            vec![program],
+            "check.def:4:5",
+            "\"Page.item\" is declared here",
+        );
+    }
+
+    #[test]
+    fn error_location_arguments_length() {
+        let program = "unit test;
+
+module TestModule {
+    proc test(a: i32) {
+        @affect test2(a)
+    }
+
+    proc test2(a: i32, b: i32) {}
+}";
+        assert_error_at(vec![program], "check.def:5:17");
+    }
+
+    #[test]
+    fn error_location_type_unmatch() {
+        let program = "unit test;
+
+data A {}
+
+module TestModule {
+    func test(a: A) -> i32 {
+        @return v
+        @spawn v: i32 {
+            use a;
+            let v2 = test2(a);
+            return v2;
+        }
+    }
+
+    func test2(a: i32) -> i32 {
+        @return v
+        @spawn v: i32
+    }
+}";
+        assert_error_at(vec![program], "check.def:10:28");
+        assert_label_at(
+            vec![program],
+            "check.def:15:16",
+            "expected type declared here",
+        );
+    }
+
+    #[test]
+    fn error_location_member_not_defined() {
+        let program = "unit test;
+
+data A {
+    x: i32
+}
+
+module TestModule {
+    func test(a: A) -> i32 {
+        @return v
+        @spawn v: i32 {
+            use a;
+            let v2 = test2(a.y);
+            return v2;
+        }
+    }
+
+    func test2(x: i32) -> i32 {
+        @return v
+        @spawn v: i32
+    }
+}";
+        assert_error_at(vec![program], "check.def:12:28");
+    }
+
+    #[test]
+    fn collect_multiple_errors() {
+        let program = "
+unit test;
+
+data A {
+    x: B
+}
+
+data A {}
+
+module M {
+    func f(a: A) -> i32 {
+        @return a
+    }
+
+    func g() -> C {
+        @return c
+        @spawn c: C
+    }
+}";
+        let mut parser = Parser::default();
+        parser.parse("check.def".to_string(), program).unwrap();
+        let (system, diagnostics) = parser.check_with_diagnostics();
+        assert!(system.is_none());
+        assert_eq!(diagnostics.len(), 4);
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+    }
+
+    #[test]
+    fn suggest_similar_name() {
+        let program = "
+unit test;
+
+data Box {
+    value: i32
+}
+
+module M {
+    proc f(box: Bax) {}
+}";
+        assert_helps(vec![program], vec!["did you mean \"Box\"?"]);
+
+        let program = "
+unit test;
+
+data Box {
+    value: i32
+}
+
+module M {
+    func f(box: Box) -> i32 {
+        @return v
+        @spawn v: i32 {
+            use box;
+            let v2 = M.g(box.valeu);
+            return v2;
+        }
+    }
+
+    func g(x: i32) -> i32 {
+        @return x
+    }
+}";
+        assert_helps(vec![program], vec!["did you mean \"value\"?"]);
+
+        let program = "
+unit test;
+
+data Box {
+    value: i32
+}
+
+module M {
+    proc f(box: Box) {
+        @affect M.fff(box)
+    }
+
+    proc ff(box: Box) {
+        @modify box
+    }
+}";
+        assert_helps(vec![program], vec!["did you mean \"ff\"?"]);
+
+        let program = "
+unit test;
+
+data Box {
+    value: i32
+}
+
+module M {
+    func f(box: Box) -> Box {
+        @return bax
+    }
+}";
+        assert_helps(vec![program], vec!["did you mean \"box\"?"]);
+    }
+
+    #[test]
+    fn suggest_missing_import() {
+        let program1 = "
+unit test;
+
+module M {
+    proc f(box: Box) {}
+}";
+        let program2 = "
+unit test2;
+
+pub data Box {
+    value: i32
+}";
+        assert_helps(
+            vec![program1, program2],
+            vec!["\"Box\" is defined in unit \"test2\", consider importing it: `from test2 import Box;`"],
+        );
+    }
+
+    #[test]
+    fn no_suggestion_for_dissimilar_name() {
+        let program = "
+unit test;
+
+data Box {
+    value: i32
+}
+
+module M {
+    proc f(box: Queue) {}
+}";
+        assert_helps(vec![program], vec![]);
+    }
+
+    fn assert_helps(programs: Vec<&str>, helps: Vec<&str>) {
+        let mut parser = Parser::default();
+        for program in programs {
+            parser.parse("check.def".to_string(), program).unwrap();
+        }
+        let err = parser.check().unwrap_err().downcast::<PError>().unwrap();
+        assert_eq!(err.helps(), &helps, "{}", err);
+    }
+
+    fn assert_error_at(programs: Vec<&str>, loc: &str) {
+        let mut parser = Parser::default();
+        for program in programs {
+            parser.parse("check.def".to_string(), program).unwrap();
+        }
+        let err = parser.check().unwrap_err();
+        assert!(
+            format!("{}", err).ends_with(&format!("(at {})", loc)),
+            "{}",
+            err
+        );
+    }
+
+    fn assert_label_at(programs: Vec<&str>, loc: &str, message: &str) {
+        let mut parser = Parser::default();
+        for program in programs {
+            parser.parse("check.def".to_string(), program).unwrap();
+        }
+        let err = parser.check().unwrap_err().downcast::<PError>().unwrap();
+        let (label_loc, label_message) = &err.labels()[0];
+        assert_eq!(format!("{}", label_loc), loc);
+        assert_eq!(label_message, message);
+    }
+
     fn check(programs: Vec<&str>) {
         let mut parser = Parser::default();
         for program in programs {
-            parser
-                .parse("check.def".to_string(), &program.to_string())
-                .unwrap();
+            parser.parse("check.def".to_string(), program).unwrap();
         }
         parser.check().unwrap();
     }
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use super::utils::graph;
+use crate::error::{PError, PErrorKind};
+use crate::location::Location;
+use crate::structure::unchecked;
+
+// Unit間のimportの依存関係
+// ※unitsはUnitの完全な名前の一覧 (探索は宣言順に行われる)
+// ※namesはUnitの完全な名前と，報告に用いる名前(std.io)の対応
+// ※edgesはUnitの完全な名前ごとの，importしているUnitとその(最初の)importの位置の一覧
+pub struct ImportGraph {
+    units: Vec<String>,
+    names: HashMap<String, String>,
+    edges: HashMap<String, Vec<(String, Location)>>,
+}
+
+impl ImportGraph {
+    pub fn new(system: &unchecked::SysDCSystem) -> ImportGraph {
+        let mut graph = ImportGraph {
+            units: vec![],
+            names: HashMap::new(),
+            edges: HashMap::new(),
+        };
+        for unit in &system.units {
+            let unit_name = unit.name.get_full_name();
+            graph
+                .names
+                .insert(unit_name.clone(), unit.name.name.clone());
+            graph.units.push(unit_name);
+        }
+        for unit in &system.units {
+            graph.listup_edges(unit);
+        }
+        graph
+    }
+
+    // ※自身のimportや，存在しないUnitのimportは対象外 (後者は検査時にエラーとして報告される)
+    fn listup_edges(&mut self, unit: &unchecked::SysDCUnit) {
+        let from = unit.name.get_full_name();
+        let mut edges = self.edges.remove(&from).unwrap_or_default();
+        for import in &unit.imports {
+            let (to, loc) = match import {
+                unchecked::SysDCImport::Item { target, .. } => {
+                    (target.namespace.clone(), &target.loc)
+                }
+                unchecked::SysDCImport::Wildcard(target)
+                | unchecked::SysDCImport::Unit { target, .. } => {
+                    (target.get_full_name(), &target.loc)
+                }
+            };
+            let already_imported = edges.iter().any(|(unit, _)| unit == &to);
+            if to == from || already_imported || !self.units.contains(&to) {
+                continue;
+            }
+            edges.push((to, loc.clone()));
+        }
+        self.edges.insert(from, edges);
+    }
+
+    // 循環しているimportを探し，見つかった循環ごとにその経路(a -> b -> c -> a)を報告する
+    // ※報告される位置は循環の始点となるimport，各importの位置はラベルとして付与される
+    pub fn find_cycles(&self) -> Vec<PError> {
+        graph::find_cycles(&self.units, &self.edges)
+            .iter()
+            .map(|cycle| self.report_cycle(cycle))
+            .collect()
+    }
+
+    fn report_cycle(&self, cycle: &[(&String, &Location)]) -> PError {
+        let names = cycle
+            .iter()
+            .map(|(unit, _)| self.names[*unit].as_str())
+            .chain([self.names[cycle[0].0].as_str()])
+            .collect::<Vec<&str>>();
+        let mut err =
+            PError::from(PErrorKind::ImportCycle(names.join(" -> "))).with_loc(cycle[0].1.clone());
+        for (idx, (_, loc)) in cycle.iter().enumerate().skip(1) {
+            let message = format!("\"{}\" imports \"{}\" here", names[idx], names[idx + 1]);
+            err = err.with_label((*loc).clone(), &message);
+        }
+        err
+    }
+}
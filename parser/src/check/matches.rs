@@ -1,32 +1,88 @@
 use super::utils::define::DefinesManager;
 use crate::error::{PError, PErrorKind};
 use crate::name::Name;
-use crate::structure::{SysDCAnnotation, SysDCFunction, SysDCSpawnDetail, SysDCSystem};
+use crate::structure::{
+    SysDCAnnotation, SysDCFunction, SysDCInterface, SysDCModule, SysDCSpawnDetail, SysDCSystem,
+    Visibility,
+};
 use crate::types::{Type, TypeKind};
 
 pub struct TypeMatchChecker<'a> {
     def_manager: &'a DefinesManager,
-    imports: &'a Vec<Name>,
 }
 
 impl<'a> TypeMatchChecker<'a> {
-    pub fn check(
-        system: &SysDCSystem,
-        def_manager: &'a DefinesManager,
-        imports: &'a Vec<Name>,
-    ) -> anyhow::Result<()> {
-        let checker = TypeMatchChecker {
-            def_manager,
-            imports,
-        };
+    // 関数ごと(およびModuleが実装するInterfaceごと)に検査を行い，見つかったエラーをまとめて返す
+    pub fn check(system: &SysDCSystem, def_manager: &'a DefinesManager) -> Vec<anyhow::Error> {
+        let checker = TypeMatchChecker { def_manager };
+        let mut errors = vec![];
         for unit in &system.units {
             for module in &unit.modules {
+                for interface in &module.implements {
+                    errors.extend(checker.check_implements(system, module, interface));
+                }
                 for func in &module.functions {
-                    checker.check_function(func)?;
+                    if let Err(err) = checker.check_function(func) {
+                        errors.push(err);
+                    }
                 }
             }
         }
-        Ok(())
+        errors
+    }
+
+    // moduleがinterfaceに宣言されているすべての関数を，同じシグネチャで定義しているかを確認する
+    // ※引数は名前ではなく，型と順序のみを比較する
+    // ※Interfaceに宣言された関数は常に公開されるため，実装する関数にはpubが必要となる
+    fn check_implements(
+        &self,
+        system: &SysDCSystem,
+        module: &SysDCModule,
+        interface: &Name,
+    ) -> Vec<anyhow::Error> {
+        let interface = match find_interface(system, interface) {
+            Some(interface) => interface,
+            None => return vec![],
+        };
+        let mut errors = vec![];
+        for req_func in &interface.functions {
+            let act_func = module
+                .functions
+                .iter()
+                .find(|func| func.name.name == req_func.name.name);
+            match act_func {
+                Some(act_func) if !has_same_signature(act_func, req_func) => {
+                    let err = PError::from(PErrorKind::SignatureUnmatch(
+                        act_func.name.name.clone(),
+                        interface.name.name.clone(),
+                    ))
+                    .with_loc(act_func.name.loc.clone())
+                    .with_label(req_func.name.loc.clone(), "signature declared here");
+                    errors.push(err.into());
+                }
+                Some(act_func) if act_func.visibility != Visibility::Public => {
+                    let err = PError::from(PErrorKind::SignatureUnmatch(
+                        act_func.name.name.clone(),
+                        interface.name.name.clone(),
+                    ))
+                    .with_loc(act_func.name.loc.clone())
+                    .with_label(req_func.name.loc.clone(), "signature declared here")
+                    .with_help("functions implementing an interface must be `pub`");
+                    errors.push(err.into());
+                }
+                Some(_) => {}
+                None => {
+                    let err = PError::from(PErrorKind::FuncNotImplemented(
+                        req_func.name.name.clone(),
+                        interface.name.name.clone(),
+                    ))
+                    .with_loc(module.name.loc.clone())
+                    .with_label(req_func.name.loc.clone(), "declared here");
+                    errors.push(err.into());
+                }
+            }
+        }
+        errors
     }
 
     fn check_function(&self, func: &SysDCFunction) -> anyhow::Result<()> {
@@ -34,26 +90,29 @@ impl<'a> TypeMatchChecker<'a> {
             let req_ret_type = &func.returns.1;
             let act_ret_type = self
                 .def_manager
-                .resolve_from_name(func.returns.0.clone(), self.imports)?
+                .resolve_from_name(func.returns.0.clone())?
                 .1;
-            if req_ret_type != &act_ret_type {
+            if !req_ret_type.is_compatible(&act_ret_type) {
                 return Err(PError::from(PErrorKind::TypeUnmatch2(
-                    req_ret_type.clone(),
-                    act_ret_type,
+                    Box::new(req_ret_type.clone()),
+                    Box::new(act_ret_type),
                 ))
+                .with_loc(func.returns.0.loc.clone())
                 .into());
             }
         }
 
         for annotation in &func.annotations {
             match annotation {
-                SysDCAnnotation::Affect { func, args } => {
-                    self.check_annotation_affect(func, args)?
-                }
+                SysDCAnnotation::Affect {
+                    func,
+                    type_args,
+                    args,
+                } => self.check_annotation_affect(func, type_args, args)?,
+                SysDCAnnotation::Modify { target, .. } => self.check_annotation_modify(target)?,
                 SysDCAnnotation::Spawn { result, details } => {
                     self.check_annotation_spawn(result, details)?
                 }
-                _ => {}
             }
         }
 
@@ -63,21 +122,21 @@ impl<'a> TypeMatchChecker<'a> {
     fn check_annotation_affect(
         &self,
         (func, _): &(Name, Type),
-        args: &Vec<(Name, Type)>,
+        type_args: &[Type],
+        args: &[(Name, Type)],
     ) -> anyhow::Result<()> {
-        let act_arg_types = args;
-        let req_arg_types = self.def_manager.get_args_type(func, self.imports)?;
-        if act_arg_types.len() != req_arg_types.len() {
-            return Err(PError::from(PErrorKind::ArgumentsLengthNotMatch).into());
-        }
-        for ((_, act_arg_type), req_arg_type) in act_arg_types.iter().zip(req_arg_types.iter()) {
-            if act_arg_type != req_arg_type {
-                return Err(PError::from(PErrorKind::TypeUnmatch2(
-                    req_arg_type.clone(),
-                    act_arg_type.clone(),
-                ))
-                .into());
-            }
+        self.check_args(func, type_args, args)
+    }
+
+    // 変更の対象はData型の変数(引数, spawnされた変数)でなければならない
+    // ※useされる名前は読み取られるのみであり，型は問わない (変数であることは型解決時に確認される)
+    fn check_annotation_modify(&self, (target, target_type): &(Name, Type)) -> anyhow::Result<()> {
+        if target_type.kind != TypeKind::Data {
+            return Err(
+                PError::from(PErrorKind::ModifyTargetNotData(target.name.clone()))
+                    .with_loc(target.loc.clone())
+                    .into(),
+            );
         }
         Ok(())
     }
@@ -89,40 +148,75 @@ impl<'a> TypeMatchChecker<'a> {
     ) -> anyhow::Result<()> {
         for detail in details {
             match detail {
-                SysDCSpawnDetail::Return(_, act_ret_type) => {
-                    if &result.1 != act_ret_type {
-                        return Err(PError::from(PErrorKind::TypeUnmatch2(
-                            result.1.clone(),
-                            act_ret_type.clone(),
-                        ))
-                        .into());
-                    }
+                SysDCSpawnDetail::Return(name, act_ret_type)
+                    if !result.1.is_compatible(act_ret_type) =>
+                {
+                    return Err(PError::from(PErrorKind::TypeUnmatch2(
+                        Box::new(result.1.clone()),
+                        Box::new(act_ret_type.clone()),
+                    ))
+                    .with_loc(name.loc.clone())
+                    .into());
                 }
                 SysDCSpawnDetail::LetTo {
                     func: (func, _),
+                    type_args,
                     args,
                     ..
-                } => {
-                    let act_arg_types = args;
-                    let req_arg_types = self.def_manager.get_args_type(func, self.imports)?;
-                    if act_arg_types.len() != req_arg_types.len() {
-                        return Err(PError::from(PErrorKind::ArgumentsLengthNotMatch).into());
-                    }
-                    for ((_, act_arg_type), req_arg_type) in
-                        act_arg_types.iter().zip(req_arg_types.iter())
-                    {
-                        if act_arg_type != req_arg_type {
-                            return Err(PError::from(PErrorKind::TypeUnmatch2(
-                                req_arg_type.clone(),
-                                act_arg_type.clone(),
-                            ))
-                            .into());
-                        }
-                    }
-                }
+                } => self.check_args(func, type_args, args)?,
                 _ => {}
             }
         }
         Ok(())
     }
+
+    // 関数呼び出し(affect, let)の引数の数と型が定義と一致するかを確認する
+    fn check_args(
+        &self,
+        func: &Name,
+        type_args: &[Type],
+        args: &[(Name, Type)],
+    ) -> anyhow::Result<()> {
+        let act_args = args;
+        let req_args = self.def_manager.get_args(func, type_args)?;
+        if act_args.len() != req_args.len() {
+            return Err(PError::from(PErrorKind::ArgumentsLengthNotMatch)
+                .with_loc(func.loc.clone())
+                .into());
+        }
+        for ((act_arg_name, act_arg_type), (req_arg_name, req_arg_type)) in
+            act_args.iter().zip(req_args.iter())
+        {
+            if !req_arg_type.is_compatible(act_arg_type) {
+                return Err(PError::from(PErrorKind::TypeUnmatch2(
+                    Box::new(req_arg_type.clone()),
+                    Box::new(act_arg_type.clone()),
+                ))
+                .with_loc(act_arg_name.loc.clone())
+                .with_label(req_arg_name.loc.clone(), "expected type declared here")
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn find_interface<'a>(system: &'a SysDCSystem, name: &Name) -> Option<&'a SysDCInterface> {
+    system
+        .units
+        .iter()
+        .flat_map(|unit| unit.interfaces.iter())
+        .find(|interface| &interface.name == name)
+}
+
+// 引数の型(順序を含む)と返り値の型が一致するかどうか
+fn has_same_signature(func: &SysDCFunction, other: &SysDCFunction) -> bool {
+    func.kind == other.kind
+        && func.args.len() == other.args.len()
+        && func
+            .args
+            .iter()
+            .zip(other.args.iter())
+            .all(|((_, a), (_, b))| a == b)
+        && func.returns.1 == other.returns.1
 }
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use super::utils::graph;
+use crate::error::{PError, PErrorKind};
+use crate::location::Location;
+use crate::structure::{SysDCData, SysDCSystem};
+use crate::types::{Type, TypeKind};
+
+// 包含関係を辿る際に経由する箇所 (A.a，合成元の場合はA) とその位置
+type Step = (String, Location);
+
+// Data間の包含関係 (メンバの型，合成元)
+// ※datasはDataの完全な名前の一覧 (探索は宣言順に行われる)
+// ※namesはDataの完全な名前と，報告に用いる名前(Name)の対応
+// ※edgesはDataの完全な名前ごとの，包含しているDataとそこまでに経由する箇所の一覧
+// ※List, Map, Optionの型引数として与えられたDataは辿らない (値を持たずに構築できるため)
+pub struct DataGraph<'a> {
+    datas: Vec<String>,
+    defs: HashMap<String, &'a SysDCData>,
+    edges: HashMap<String, Vec<(String, Vec<Step>)>>,
+}
+
+impl<'a> DataGraph<'a> {
+    pub fn new(system: &'a SysDCSystem) -> DataGraph<'a> {
+        let mut graph = DataGraph {
+            datas: vec![],
+            defs: HashMap::new(),
+            edges: HashMap::new(),
+        };
+        for data in system.units.iter().flat_map(|unit| &unit.data) {
+            let data_name = data.name.get_full_name();
+            graph.defs.insert(data_name.clone(), data);
+            graph.datas.push(data_name);
+        }
+        for data in system.units.iter().flat_map(|unit| &unit.data) {
+            let mut edges = vec![];
+            for (step, types) in contents(data) {
+                for (to, steps) in graph.contained(types, &mut vec![]) {
+                    edges.push((to, [vec![step.clone()], steps].concat()));
+                }
+            }
+            graph.edges.insert(data.name.get_full_name(), edges);
+        }
+        graph
+    }
+
+    // 自身を(間接的に)包含しているDataを探し，見つかった循環ごとにその経路(A.b -> B.a -> A)を報告する
+    // ※報告される位置は循環の始点となるメンバ，経由する各メンバの位置はラベルとして付与される
+    pub fn find_cycles(&self) -> Vec<anyhow::Error> {
+        graph::find_cycles(&self.datas, &self.edges)
+            .iter()
+            .map(|cycle| self.report_cycle(cycle).into())
+            .collect()
+    }
+
+    // 型typesの値が包含するDataと，そこまでに経由する箇所の一覧
+    // ※型引数を持つDataは，メンバを通して包含する型引数のDataも含む (Page<A> の item: T によるA)
+    // ※visitingは型引数の探索中のData (自身を型引数ごと包含している場合に探索を打ち切るため)
+    fn contained(&self, types: &Type, visiting: &mut Vec<String>) -> Vec<(String, Vec<Step>)> {
+        let refs = match (&types.kind, &types.refs) {
+            (TypeKind::Data, Some(refs)) => refs.get_full_name(),
+            _ => return vec![],
+        };
+        let mut found = vec![(refs.clone(), vec![])];
+        for (idx, steps) in self.params_contained(&refs, visiting) {
+            if let Some(arg) = types.params.get(idx) {
+                for (to, rest) in self.contained(arg, visiting) {
+                    found.push((to, [steps.clone(), rest].concat()));
+                }
+            }
+        }
+        found
+    }
+
+    // Data(data)の値が包含する型パラメータの位置と，そこまでに経由する箇所の一覧
+    fn params_contained(&self, data: &str, visiting: &mut Vec<String>) -> Vec<(usize, Vec<Step>)> {
+        let def = match self.defs.get(data) {
+            Some(def) if !def.params.is_empty() => *def,
+            _ => return vec![],
+        };
+        if visiting.iter().any(|visited| visited == data) {
+            return vec![];
+        }
+        visiting.push(data.to_string());
+
+        let mut found = vec![];
+        for (step, types) in contents(def) {
+            for (idx, steps) in self.params_in(types, def, visiting) {
+                found.push((idx, [vec![step.clone()], steps].concat()));
+            }
+        }
+        visiting.pop();
+        found
+    }
+
+    // 型typesの値が包含する，Data(data)の型パラメータの位置と，そこまでに経由する箇所の一覧
+    fn params_in(
+        &self,
+        types: &Type,
+        data: &SysDCData,
+        visiting: &mut Vec<String>,
+    ) -> Vec<(usize, Vec<Step>)> {
+        match (&types.kind, &types.refs) {
+            (TypeKind::Param, Some(refs)) => data
+                .params
+                .iter()
+                .position(|param| param == refs)
+                .map(|idx| vec![(idx, vec![])])
+                .unwrap_or_default(),
+            (TypeKind::Data, Some(refs)) => {
+                let mut found = vec![];
+                for (arg_idx, steps) in self.params_contained(&refs.get_full_name(), visiting) {
+                    if let Some(arg) = types.params.get(arg_idx) {
+                        for (idx, rest) in self.params_in(arg, data, visiting) {
+                            found.push((idx, [steps.clone(), rest].concat()));
+                        }
+                    }
+                }
+                found
+            }
+            _ => vec![],
+        }
+    }
+
+    fn report_cycle(&self, cycle: &[(&String, &Vec<Step>)]) -> PError {
+        let steps = cycle
+            .iter()
+            .flat_map(|(_, steps)| steps.iter())
+            .collect::<Vec<&Step>>();
+        let chain = steps
+            .iter()
+            .map(|(via, _)| via.as_str())
+            .chain([self.defs[cycle[0].0].name.name.as_str()])
+            .collect::<Vec<&str>>();
+        let mut err = PError::from(PErrorKind::RecursiveData(chain.join(" -> ")))
+            .with_loc(steps[0].1.clone());
+        for (via, loc) in steps.iter().skip(1) {
+            let message = format!("\"{}\" is declared here", via);
+            err = err.with_label(loc.clone(), &message);
+        }
+        err
+    }
+}
+
+// Dataの値が直接包含する型 (合成元, メンバ) と，それを記述した箇所の一覧
+fn contents(data: &SysDCData) -> Vec<(Step, &Type)> {
+    let mut contents = vec![];
+    for base in &data.extends {
+        contents.push(((data.name.name.clone(), data.name.loc.clone()), base));
+    }
+    for (name, types) in &data.members {
+        let via = format!("{}.{}", data.name.name, name.name);
+        contents.push(((via, name.loc.clone()), types));
+    }
+    contents
+}
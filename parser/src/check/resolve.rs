@@ -1,56 +1,71 @@
 use super::utils::define::DefinesManager;
+use crate::error::{PError, PErrorKind};
 use crate::name::Name;
 use crate::structure::unchecked;
 use crate::structure::{
-    SysDCAnnotation, SysDCData, SysDCFunction, SysDCModule, SysDCSpawnDetail, SysDCSystem,
-    SysDCUnit,
+    SysDCAnnotation, SysDCData, SysDCEnum, SysDCFunction, SysDCInterface, SysDCModule,
+    SysDCSpawnDetail, SysDCSystem, SysDCUnit,
 };
-use crate::types::{Type, TypeKind};
+use crate::types::unchecked::{Type as UncheckedType, TypeKind as UncheckedTypeKind};
+use crate::types::Type;
 
 pub struct TypeResolver<'a> {
     def_manager: &'a DefinesManager,
-    imports: &'a Vec<Name>,
 }
 
 impl<'a> TypeResolver<'a> {
+    // 解決に失敗した要素は結果から取り除かれ，エラーはまとめて返される
     pub fn resolve(
         system: unchecked::SysDCSystem,
         def_manager: &'a DefinesManager,
-        imports: &'a Vec<Name>,
-    ) -> anyhow::Result<SysDCSystem> {
-        let mut resolver = TypeResolver {
-            def_manager,
-            imports,
-        };
+    ) -> (SysDCSystem, Vec<anyhow::Error>) {
+        let mut resolver = TypeResolver { def_manager };
         system.convert(|unit| resolver.resolve_unit(unit))
     }
 
-    fn resolve_unit(&mut self, unit: unchecked::SysDCUnit) -> anyhow::Result<SysDCUnit> {
+    fn resolve_unit(&mut self, unit: unchecked::SysDCUnit) -> (SysDCUnit, Vec<anyhow::Error>) {
         unit.convert(
             |data| self.resolve_data(data),
+            |_enum| self.resolve_enum(_enum),
+            |interface| self.resolve_interface(interface),
             |module| self.resolve_module(module),
         )
     }
 
     fn resolve_data(&self, data: unchecked::SysDCData) -> anyhow::Result<SysDCData> {
-        data.convert(|(name, types): (Name, Type)| {
-            if types.kind.is_primitive() {
-                Ok((name, types))
-            } else {
-                self.def_manager
-                    .resolve_from_type((name, types), self.imports)
-            }
-        })
+        self.def_manager.check_members(&data.name)?;
+        data.convert(
+            |base| Ok(self.resolve_from_type(base)?.1),
+            |member| self.resolve_from_type(member),
+        )
+    }
+
+    fn resolve_enum(&self, _enum: unchecked::SysDCEnum) -> anyhow::Result<SysDCEnum> {
+        _enum.convert(|variant| variant.convert(|member| self.resolve_from_type(member)))
     }
 
-    fn resolve_module(&self, module: unchecked::SysDCModule) -> anyhow::Result<SysDCModule> {
-        module.convert(|func| self.resolve_function(func))
+    fn resolve_interface(
+        &self,
+        interface: unchecked::SysDCInterface,
+    ) -> (SysDCInterface, Vec<anyhow::Error>) {
+        interface.convert(|func| self.resolve_function(func))
+    }
+
+    fn resolve_module(&self, module: unchecked::SysDCModule) -> (SysDCModule, Vec<anyhow::Error>) {
+        module.convert(
+            |interface| {
+                let loc = interface.loc.clone();
+                let interface = self.def_manager.resolve_interface(interface)?;
+                Ok(interface.with_loc(loc))
+            },
+            |func| self.resolve_function(func),
+        )
     }
 
     fn resolve_function(&self, func: unchecked::SysDCFunction) -> anyhow::Result<SysDCFunction> {
-        let a_converter = |arg| self.def_manager.resolve_from_type(arg, self.imports);
-        let r_converter = |returns: (Name, Type)| {
-            let returns = self.def_manager.resolve_from_type(returns, self.imports)?;
+        let a_converter = |arg| self.resolve_from_type(arg);
+        let r_converter = |returns: (Name, UncheckedType)| {
+            let returns = self.resolve_from_type(returns)?;
             Ok(returns)
         };
         let ann_converter = |annotation| self.resolve_annotation(annotation);
@@ -62,23 +77,24 @@ impl<'a> TypeResolver<'a> {
         annotation: unchecked::SysDCAnnotation,
     ) -> anyhow::Result<SysDCAnnotation> {
         let a_converter = |func, args| {
-            let func = self.def_manager.resolve_from_type(func, self.imports)?;
+            let type_args = self.resolve_type_args(&func)?;
+            let func = self.resolve_from_type(func)?;
             let mut rargs = vec![];
             for (name, _) in args {
-                rargs.push(self.def_manager.resolve_from_name(name, self.imports)?);
+                rargs.push(self.resolve_from_name(name)?);
             }
-            Ok((func, rargs))
+            Ok((func, type_args, rargs))
         };
         let m_converter = |(name, _), uses| {
-            let target = self.def_manager.resolve_from_name(name, self.imports)?;
+            let target = self.resolve_from_name(name)?;
             let mut ruses = vec![];
             for (name, _) in uses {
-                ruses.push(self.def_manager.resolve_from_name(name, self.imports)?);
+                ruses.push(self.resolve_from_name(name)?);
             }
             Ok((target, ruses))
         };
         let s_converter = |(name, _), details| {
-            let result = self.def_manager.resolve_from_name(name, self.imports)?;
+            let result = self.resolve_from_name(name)?;
             let details = self.resolve_annotation_spawn_details(details)?;
             Ok((result, details))
         };
@@ -89,26 +105,30 @@ impl<'a> TypeResolver<'a> {
         &self,
         details: Vec<unchecked::SysDCSpawnDetail>,
     ) -> anyhow::Result<Vec<SysDCSpawnDetail>> {
-        let ur_converter =
-            |(name, _): (Name, Type)| self.def_manager.resolve_from_name(name, self.imports);
-        let l_converter = |name: Name, func: (Name, Type), args: Vec<(Name, Type)>| {
-            if let Type {
-                kind: TypeKind::Unsolved(_),
-                ..
-            } = func.1
-            {
-                let mut rargs = vec![];
-                for (arg_name, _) in args {
-                    let (arg_name, arg_type) = self
-                        .def_manager
-                        .resolve_from_name(arg_name.clone(), self.imports)?;
-                    rargs.push((arg_name, arg_type));
+        let ur_converter = |(name, _): (Name, UncheckedType)| self.resolve_from_name(name);
+        let l_converter =
+            |name: Name, func: (Name, UncheckedType), args: Vec<(Name, UncheckedType)>| {
+                if let UncheckedType {
+                    kind: UncheckedTypeKind::Unsolved(_),
+                    ..
+                } = func.1
+                {
+                    let mut rargs = vec![];
+                    for (arg_name, _) in args {
+                        let (arg_name, arg_type) = self.resolve_from_name(arg_name.clone())?;
+                        rargs.push((arg_name, arg_type));
+                    }
+                    let type_args = self.resolve_type_args(&func)?;
+                    let func = self.resolve_from_type(func)?;
+                    return Ok((name, func, type_args, rargs));
                 }
-                let func = self.def_manager.resolve_from_type(func, self.imports)?;
-                return Ok((name, func, rargs));
-            }
-            panic!("Internal Error")
-        };
+                // ※プリミティブ型やList, Map, Optionは関数として呼び出せない
+                let message = format!("\"{}\" is not a function", func.0.name);
+                Err(PError::from(PErrorKind::NotDefined(func.0.name))
+                    .with_loc(func.0.loc)
+                    .with_help(&message)
+                    .into())
+            };
 
         let mut rdetails = vec![];
         for detail in details {
@@ -116,4 +136,33 @@ impl<'a> TypeResolver<'a> {
         }
         Ok(rdetails)
     }
+
+    // 解決後の名前は定義を指すが，位置情報は参照元(記述された位置)のものを引き継ぐ
+    fn resolve_from_type(
+        &self,
+        (name, types): (Name, UncheckedType),
+    ) -> anyhow::Result<(Name, Type)> {
+        let loc = name.loc.clone();
+        let (name, types) = self.def_manager.resolve_from_type((name, types))?;
+        Ok((name.with_loc(loc), types))
+    }
+
+    // 関数呼び出し時にModuleへ与えられた型引数を解決する (Repository<User>.save のUser)
+    fn resolve_type_args(
+        &self,
+        (name, types): &(Name, UncheckedType),
+    ) -> anyhow::Result<Vec<Type>> {
+        let mut type_args = vec![];
+        for param in &types.params {
+            let (_, param) = self.resolve_from_type((name.clone(), param.clone()))?;
+            type_args.push(param);
+        }
+        Ok(type_args)
+    }
+
+    fn resolve_from_name(&self, name: Name) -> anyhow::Result<(Name, Type)> {
+        let loc = name.loc.clone();
+        let (name, types) = self.def_manager.resolve_from_name(name)?;
+        Ok((name.with_loc(loc), types))
+    }
 }
@@ -0,0 +1,200 @@
+use super::utils::define::DefinesManager;
+use crate::error::{PError, PErrorKind};
+use crate::location::Location;
+use crate::name::Name;
+use crate::structure::unchecked;
+use crate::structure::{
+    SysDCAnnotation, SysDCData, SysDCEnum, SysDCFunction, SysDCSpawnDetail, SysDCSystem,
+};
+use crate::types::{Type, TypeKind};
+
+// すべてのUnitに記述された規則(rule { deny .. -> ..; })を対象に，禁止されたUnit間の依存を検査する
+pub struct RuleChecker<'a> {
+    rules: Vec<unchecked::SysDCRule>,
+    def_manager: &'a DefinesManager,
+}
+
+impl<'a> RuleChecker<'a> {
+    pub fn new(
+        system: &unchecked::SysDCSystem,
+        def_manager: &'a DefinesManager,
+    ) -> RuleChecker<'a> {
+        let rules = system
+            .units
+            .iter()
+            .flat_map(|unit| unit.rules.iter().cloned())
+            .collect();
+        RuleChecker { rules, def_manager }
+    }
+
+    // importによる依存を検査する (型解決の前に行う)
+    pub fn check_imports(&self, system: &unchecked::SysDCSystem) -> Vec<anyhow::Error> {
+        let mut errors = vec![];
+        if self.rules.is_empty() {
+            return errors;
+        }
+        for unit in &system.units {
+            let unit_name = unit.name.get_full_name();
+            for import in &unit.imports {
+                let (target, loc) = match import {
+                    unchecked::SysDCImport::Item { target, .. } => {
+                        (target.namespace.clone(), &target.loc)
+                    }
+                    unchecked::SysDCImport::Wildcard(target)
+                    | unchecked::SysDCImport::Unit { target, .. } => {
+                        (target.get_full_name(), &target.loc)
+                    }
+                };
+                if let Err(err) = self.check_dependency(&unit_name, &target, loc) {
+                    errors.push(err);
+                }
+            }
+        }
+        errors
+    }
+
+    // @affect, letでの関数呼び出し，および型としての参照による依存を検査する (型解決の後に行う)
+    // ※importを介さない参照 (親のUnitの定義，完全な名前での参照) も対象となる
+    pub fn check(&self, system: &SysDCSystem) -> Vec<anyhow::Error> {
+        let mut errors = vec![];
+        if self.rules.is_empty() {
+            return errors;
+        }
+        for unit in &system.units {
+            let unit_name = unit.name.get_full_name();
+            let mut refs = vec![];
+            for data in &unit.data {
+                refs.extend(collect_refs_in_data(data));
+            }
+            for _enum in &unit.enums {
+                refs.extend(collect_refs_in_enum(_enum));
+            }
+            for interface in &unit.interfaces {
+                for func in &interface.functions {
+                    refs.extend(collect_refs_in_function(func));
+                }
+            }
+            for module in &unit.modules {
+                for interface in &module.implements {
+                    refs.push((interface, &interface.loc));
+                }
+                for func in &module.functions {
+                    refs.extend(collect_refs_in_function(func));
+                }
+            }
+            for (refs, loc) in refs {
+                if let Some(target) = self.def_manager.get_unit(refs) {
+                    if let Err(err) = self.check_dependency(&unit_name, target, loc) {
+                        errors.push(err);
+                    }
+                }
+            }
+        }
+        errors
+    }
+
+    // sourceのUnitからtargetのUnitへの依存が，いずれかの規則で禁止されていないかを確認する
+    // ※source, targetはUnitの完全な名前，locはエラー発生時に報告する位置 (依存している箇所)
+    // ※同じUnit内での依存は禁止されない
+    fn check_dependency(&self, source: &str, target: &str, loc: &Location) -> anyhow::Result<()> {
+        if source == target {
+            return Ok(());
+        }
+        let denied_by = self
+            .rules
+            .iter()
+            .find(|rule| rule.source.matches(source) && rule.target.matches(target));
+        match denied_by {
+            Some(rule) => Err(PError::from(PErrorKind::ForbiddenDependency(
+                source.trim_start_matches(".0.").to_string(),
+                target.trim_start_matches(".0.").to_string(),
+            ))
+            .with_loc(loc.clone())
+            .with_label(rule.loc.clone(), "denied by this rule")
+            .into()),
+            None => Ok(()),
+        }
+    }
+}
+
+// 以下，参照している定義と，参照している箇所の位置の一覧を返す
+// ※型には位置情報がないため，型を記述したメンバ・引数などの位置を用いる
+
+// メンバの型，合成元のData
+fn collect_refs_in_data(data: &SysDCData) -> Vec<(&Name, &Location)> {
+    let mut refs = vec![];
+    for base in &data.extends {
+        refs.extend(collect_refs_in_type(base, &data.name.loc));
+    }
+    for (name, types) in &data.members {
+        refs.extend(collect_refs_in_type(types, &name.loc));
+    }
+    refs
+}
+
+// 各バリアントのメンバの型
+fn collect_refs_in_enum(_enum: &SysDCEnum) -> Vec<(&Name, &Location)> {
+    let mut refs = vec![];
+    for variant in &_enum.variants {
+        for (name, types) in &variant.members {
+            refs.extend(collect_refs_in_type(types, &name.loc));
+        }
+    }
+    refs
+}
+
+// 引数・返り値の型，呼び出している関数(@affect, let)とその型引数，@spawnの結果の型
+fn collect_refs_in_function(func: &SysDCFunction) -> Vec<(&Name, &Location)> {
+    let mut refs = vec![];
+    for (name, types) in &func.args {
+        refs.extend(collect_refs_in_type(types, &name.loc));
+    }
+    refs.extend(collect_refs_in_type(&func.returns.1, &func.name.loc));
+    for annotation in &func.annotations {
+        match annotation {
+            SysDCAnnotation::Affect {
+                func: (called, _),
+                type_args,
+                ..
+            } => {
+                refs.push((called, &called.loc));
+                for types in type_args {
+                    refs.extend(collect_refs_in_type(types, &called.loc));
+                }
+            }
+            SysDCAnnotation::Spawn {
+                result: (result, types),
+                details,
+            } => {
+                refs.extend(collect_refs_in_type(types, &result.loc));
+                for detail in details {
+                    if let SysDCSpawnDetail::LetTo {
+                        func: (called, _),
+                        type_args,
+                        ..
+                    } = detail
+                    {
+                        refs.push((called, &called.loc));
+                        for types in type_args {
+                            refs.extend(collect_refs_in_type(types, &called.loc));
+                        }
+                    }
+                }
+            }
+            SysDCAnnotation::Modify { .. } => {}
+        }
+    }
+    refs
+}
+
+// 型が参照しているData, Enum (型引数として与えられたものを含む)
+fn collect_refs_in_type<'a>(types: &'a Type, loc: &'a Location) -> Vec<(&'a Name, &'a Location)> {
+    let mut refs = vec![];
+    if let (TypeKind::Data | TypeKind::Enum, Some(name)) = (&types.kind, &types.refs) {
+        refs.push((name, loc));
+    }
+    for param in &types.params {
+        refs.extend(collect_refs_in_type(param, loc));
+    }
+    refs
+}
@@ -1 +1,3 @@
 pub mod define;
+pub mod graph;
+pub mod suggest;
@@ -1,17 +1,59 @@
+use std::collections::{HashMap, HashSet};
+
+use super::suggest::find_similar;
 use crate::error::{PError, PErrorKind};
+use crate::location::Location;
 use crate::name::Name;
-use crate::structure::unchecked;
+use crate::structure::{unchecked, Visibility};
+use crate::types::unchecked::{Type as UncheckedType, TypeKind as UncheckedTypeKind};
 use crate::types::{Type, TypeKind};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum DefineKind {
     Data,
-    DataMember(Type),
+    DataMember(UncheckedType),
+    Enum,
+    Variant,
+    Interface,
     Module,
-    Function(Type),
-    Argument(Type),
-    Variable(Type),
+    TypeParam,
+    Function(UncheckedType),
+    Argument(UncheckedType),
+    Variable(UncheckedType),
     Use(Name),
+    Unit,
+}
+
+impl DefineKind {
+    // 型として参照される定義かどうか
+    fn is_type(&self) -> bool {
+        matches!(
+            self,
+            DefineKind::Data
+                | DefineKind::Enum
+                | DefineKind::Interface
+                | DefineKind::Module
+                | DefineKind::Function(_)
+                | DefineKind::TypeParam
+                | DefineKind::Unit
+        )
+    }
+
+    // Unitの直下に定義され，importの対象となる定義かどうか
+    fn is_unit_item(&self) -> bool {
+        matches!(
+            self,
+            DefineKind::Data | DefineKind::Enum | DefineKind::Interface | DefineKind::Module
+        )
+    }
+
+    // 値として参照される定義かどうか
+    fn is_value(&self) -> bool {
+        matches!(
+            self,
+            DefineKind::Argument(_) | DefineKind::Variable(_) | DefineKind::Use(_)
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -21,287 +63,842 @@ struct Define {
 }
 
 impl Define {
+    // ※定義に付与されたドキュメントコメントは，定義を参照する側の名前には引き継がない
     pub fn new(kind: DefineKind, refs: Name) -> Define {
-        Define { kind, refs }
+        Define {
+            kind,
+            refs: refs.with_doc(None),
+        }
     }
 }
 
-pub struct DefinesManager {
+// 定義の一覧と，namespaceごとの索引
+// ※同じnamespace内の定義は登録順に取り出される
+#[derive(Default)]
+struct DefineTable {
     defines: Vec<Define>,
+    scopes: HashMap<String, Scope>,
+}
+
+#[derive(Default)]
+struct Scope {
+    order: Vec<usize>,
+    names: HashMap<String, Vec<usize>>,
+}
+
+impl DefineTable {
+    fn push(&mut self, def: Define) {
+        let idx = self.defines.len();
+        let scope = self.scopes.entry(def.refs.namespace.clone()).or_default();
+        scope.order.push(idx);
+        scope
+            .names
+            .entry(def.refs.name.clone())
+            .or_default()
+            .push(idx);
+        self.defines.push(def);
+    }
+
+    // namespace内で，nameという名前を持つ定義の一覧
+    fn get<'a>(&'a self, namespace: &str, name: &str) -> impl Iterator<Item = &'a Define> {
+        self.scopes
+            .get(namespace)
+            .and_then(|scope| scope.names.get(name))
+            .into_iter()
+            .flatten()
+            .map(|idx| &self.defines[*idx])
+    }
+
+    // namespace内の定義の一覧
+    fn scope<'a>(&'a self, namespace: &str) -> impl Iterator<Item = &'a Define> {
+        self.scopes
+            .get(namespace)
+            .map(|scope| scope.order.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|idx| &self.defines[*idx])
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Define> {
+        self.defines.iter()
+    }
+}
+
+// ※extendsはDataの完全な名前ごとの合成元 (data AdminUser = User + { .. } のUser)
+// ※visibilitiesは定義(Data, Enum, Interface, Module, 関数)の完全な名前ごとの可視性
+// ※unitsはUnitの完全な名前の一覧
+// ※importsはUnitの完全な名前ごとのimport一覧
+pub struct DefinesManager {
+    defines: DefineTable,
+    extends: HashMap<String, Vec<(Name, UncheckedType)>>,
+    visibilities: HashMap<String, Visibility>,
+    units: HashSet<String>,
+    imports: HashMap<String, Vec<unchecked::SysDCImport>>,
+    errors: Vec<anyhow::Error>,
 }
 
 impl DefinesManager {
-    pub fn new(system: &unchecked::SysDCSystem) -> anyhow::Result<DefinesManager> {
-        let mut def_manager = DefinesManager { defines: vec![] };
-        def_manager.listup_defines(system)?;
-        Ok(def_manager)
+    // 多重定義などのエラーが見つかった場合も列挙は続け，エラーはまとめて返す
+    pub fn new(system: &unchecked::SysDCSystem) -> (DefinesManager, Vec<anyhow::Error>) {
+        let mut def_manager = DefinesManager {
+            defines: DefineTable::default(),
+            extends: HashMap::new(),
+            visibilities: HashMap::new(),
+            units: HashSet::new(),
+            imports: HashMap::new(),
+            errors: vec![],
+        };
+        def_manager.listup_defines(system);
+        let errors = std::mem::take(&mut def_manager.errors);
+        (def_manager, errors)
     }
 
-    // 与えられたnameと同じ名前を持つ定義が存在するかどうかを確認する
-    pub fn check_can_import(&self, name: &Name, imports: &Vec<Name>) -> anyhow::Result<()> {
-        match self.find(name.clone(), &name.name, imports)?.kind {
-            DefineKind::Data | DefineKind::Module => Ok(()),
-            _ => Err(PError::from(PErrorKind::NotDefined(name.name.clone())).into()),
+    // importの対象となる定義またはUnitが存在し，unitからimport可能かどうかを確認する
+    pub fn check_can_import(
+        &self,
+        import: &unchecked::SysDCImport,
+        unit: &Name,
+    ) -> anyhow::Result<()> {
+        let target = match import {
+            unchecked::SysDCImport::Item { target, .. } => target,
+            unchecked::SysDCImport::Wildcard(target)
+            | unchecked::SysDCImport::Unit { target, .. } => {
+                if self.units.contains(&target.get_full_name()) {
+                    return Ok(());
+                }
+                return Err(PError::from(PErrorKind::NotFound(target.name.clone()))
+                    .with_loc(target.loc.clone())
+                    .into());
+            }
+        };
+        let found_def = match self.find_local_def(target.clone(), &target.name) {
+            Some(def) => def,
+            None => {
+                let err = PError::from(PErrorKind::NotFound(target.name.clone()))
+                    .with_loc(target.loc.clone());
+                return Err(self
+                    .suggest_for_not_found(err, target.clone(), &target.name, DefineKind::is_type)
+                    .into());
+            }
+        };
+        if found_def.kind.is_unit_item() {
+            let importer = Name::new(unit, target.name.clone()).with_loc(target.loc.clone());
+            return self.check_visibility(&found_def.refs, &importer);
+        }
+        Err(PError::from(PErrorKind::NotDefined(target.name.clone()))
+            .with_loc(target.loc.clone())
+            .into())
+    }
+
+    // Moduleが実装するInterfaceの名前(name)を解決する
+    pub fn resolve_interface(&self, name: Name) -> anyhow::Result<Name> {
+        let found_def = self.find(name.clone(), &name.name, DefineKind::is_type)?;
+        match found_def.kind {
+            DefineKind::Interface => Ok(found_def.refs),
+            _ => Err(PError::from(PErrorKind::NotInterface(name.name))
+                .with_loc(name.loc)
+                .into()),
         }
     }
 
-    // 与えられたnameから参照可能なすべての範囲またはimports内を対象に，typesと一致する定義を探す (Data, Module, Function)
+    // 与えられたnameから参照可能なすべての範囲またはimports内を対象に，typesと一致する定義を探す (Data, Enum, Interface, Module, Function, TypeParam)
     // ※name, typesはともに関連している状態を想定
+    // ※型引数を持つData(Page<User>)は，型引数を解決した上でparamsに保持する
     pub fn resolve_from_type(
         &self,
-        (name, types): (Name, Type),
-        imports: &Vec<Name>,
+        (name, types): (Name, UncheckedType),
     ) -> anyhow::Result<(Name, Type)> {
-        if types.kind.is_primitive() || types.kind == TypeKind::Data {
-            return Ok((name, types));
+        if let Some((kind, params_len)) = types.kind.as_collection() {
+            check_params_len(
+                &name,
+                format!("{:?}", types.kind),
+                &types.params,
+                params_len,
+            )?;
+            let params = self.resolve_params(&name, types.params)?;
+            return Ok((name, Type::new(kind, None).with_params(params)));
         }
 
-        if let TypeKind::Unsolved(hint) = &types.kind {
-            let (head, tails) = split_name(hint);
-            let found_def = self.find(name.clone(), &head, imports)?;
+        if let Some(kind) = types.kind.as_primitive() {
+            check_params_len(&name, format!("{:?}", types.kind), &types.params, 0)?;
+            return Ok((name, Type::new(kind, None)));
+        }
+
+        if let UncheckedTypeKind::Unsolved(hint) = &types.kind {
+            let (mut head, mut tails) = split_name(hint);
+            let mut found_def = self.find(name.clone(), &head, DefineKind::is_type)?;
+            while let DefineKind::Unit = found_def.kind {
+                // ※Unitそのものは型として参照できない (import std.time した場合の time)
+                let rest = match tails {
+                    Some(rest) => rest,
+                    None => {
+                        return Err(PError::from(PErrorKind::IllegalAccess)
+                            .with_loc(name.loc)
+                            .into())
+                    }
+                };
+                (head, tails) = split_name(&rest);
+                found_def = self.find_in_unit(&found_def.refs, &name, &head)?;
+            }
+            let params_len = match found_def.kind {
+                DefineKind::Data | DefineKind::Module => {
+                    self.get_type_params(&found_def.refs).len()
+                }
+                _ => 0,
+            };
+            check_params_len(&name, head.clone(), &types.params, params_len)?;
+            let params = self.resolve_params(&name, types.params.clone())?;
             return match found_def.kind {
                 DefineKind::Data => match tails {
-                    Some(_) => Err(PError::from(PErrorKind::IllegalAccess).into()),
-                    None => Ok((name, Type::new(TypeKind::Data, Some(found_def.refs)))),
+                    Some(_) => Err(PError::from(PErrorKind::IllegalAccess)
+                        .with_loc(name.loc)
+                        .into()),
+                    None => Ok((
+                        name,
+                        Type::new(TypeKind::Data, Some(found_def.refs)).with_params(params),
+                    )),
                 },
-                DefineKind::Module => match tails {
-                    Some(tails) => self.get_func_in_module(&found_def.refs, &tails, imports),
-                    None => Err(PError::from(PErrorKind::MissingFunctionName).into()),
+                DefineKind::Enum => match tails {
+                    Some(_) => Err(PError::from(PErrorKind::IllegalAccess)
+                        .with_loc(name.loc)
+                        .into()),
+                    None => Ok((name, Type::new(TypeKind::Enum, Some(found_def.refs)))),
+                },
+                DefineKind::TypeParam => match tails {
+                    Some(_) => Err(PError::from(PErrorKind::IllegalAccess)
+                        .with_loc(name.loc)
+                        .into()),
+                    None => Ok((name, Type::new(TypeKind::Param, Some(found_def.refs)))),
+                },
+                DefineKind::Interface | DefineKind::Module => match tails {
+                    Some(tails) => {
+                        let (func, types) =
+                            self.get_func_in_module(&found_def.refs, &tails, &params, &name.loc)?;
+                        self.check_visibility(&func, &name)?;
+                        Ok((func, types))
+                    }
+                    None => Err(PError::from(PErrorKind::MissingFunctionName)
+                        .with_loc(name.loc)
+                        .into()),
                 },
                 DefineKind::Function(_) => {
-                    self.get_func_in_module(&name.get_namespace(true), hint, imports)
+                    self.get_func_in_module(&name.get_namespace(true), hint, &[], &name.loc)
                 }
-                _ => Err(PError::from(PErrorKind::TypeUnmatch1(types)).into()),
+                _ => Err(PError::from(PErrorKind::TypeUnmatch1(types))
+                    .with_loc(name.loc)
+                    .into()),
             };
         }
 
-        panic!("Internal Error");
+        // ※型の記述を持たない名前(UnsolvedNoHint)は，型から解決することができない
+        Err(PError::from(PErrorKind::InternalError(format!(
+            "type of \"{}\" is not specified",
+            name.name
+        )))
+        .with_loc(name.loc)
+        .into())
+    }
+
+    // 型引数の一覧を解決する
+    fn resolve_params(&self, name: &Name, params: Vec<UncheckedType>) -> anyhow::Result<Vec<Type>> {
+        let mut resolved = vec![];
+        for param in params {
+            let (_, param) = self.resolve_from_type((name.clone(), param))?;
+            resolved.push(param);
+        }
+        Ok(resolved)
+    }
+
+    // 定義(refs)がnamespaceから参照可能かどうかを確認する
+    // ※同じUnit内からは，可視性に関わらず参照可能
+    fn check_visibility(&self, refs: &Name, namespace: &Name) -> anyhow::Result<()> {
+        let visibility = match self.visibilities.get(&refs.get_full_name()) {
+            Some(visibility) => visibility,
+            None => return Ok(()),
+        };
+        let def_unit = self.get_unit(refs);
+        let ref_unit = self.get_unit(namespace);
+        let visible = def_unit == ref_unit
+            || match visibility {
+                Visibility::Private => false,
+                Visibility::Public => true,
+                Visibility::Restricted(scope) => ref_unit
+                    .map(|unit| unit.trim_start_matches(".0."))
+                    .is_some_and(|unit| unit == scope || unit.starts_with(&format!("{}.", scope))),
+            };
+        if visible {
+            return Ok(());
+        }
+        Err(PError::from(PErrorKind::PrivateItem(refs.name.clone()))
+            .with_loc(namespace.loc.clone())
+            .with_label(refs.loc.clone(), "defined here")
+            .into())
+    }
+
+    // nameが属するUnitの完全な名前
+    // ※Unitの名前は階層を持つため(std, std.io)，前方一致するもののうち最も長いものを選ぶ
+    pub fn get_unit(&self, name: &Name) -> Option<&str> {
+        let full_name = name.get_full_name();
+        let mut prefix = full_name.as_str();
+        loop {
+            if let Some(unit) = self.units.get(prefix) {
+                return Some(unit.as_str());
+            }
+            prefix = &prefix[..prefix.rfind('.')?];
+        }
     }
 
     // nameから参照可能なすべての範囲またはimports内を対象に，nameと一致する名前をもつ定義を探す (Variable)
-    pub fn resolve_from_name(
-        &self,
-        name: Name,
-        imports: &Vec<Name>,
-    ) -> anyhow::Result<(Name, Type)> {
+    pub fn resolve_from_name(&self, name: Name) -> anyhow::Result<(Name, Type)> {
         let (head, tails) = split_name(&name.name);
-        let found_def = self.find(name.clone(), &head, &vec![])?;
+        let found_def = self.find(name.clone(), &head, DefineKind::is_value)?;
         match found_def.kind {
             DefineKind::Variable(types) => {
-                let (_, types) = self.resolve_from_type((name.clone(), types), imports)?;
+                let (_, types) = self.resolve_from_type((name.clone(), types))?;
                 match types.kind {
                     TypeKind::Data => match tails {
                         Some(tails) => {
                             let (_, types) = self.get_member_in_data(
                                 types.refs.as_ref().unwrap(),
+                                &types.params,
                                 &tails,
-                                imports,
+                                &name.loc,
                             )?;
                             Ok((name, types))
                         }
                         None => Ok((found_def.refs, types)),
                     },
+                    _ if tails.is_some() => Err(PError::from(PErrorKind::IllegalAccess)
+                        .with_loc(name.loc)
+                        .into()),
                     _ => Ok((found_def.refs, types)),
                 }
             }
             DefineKind::Use(use_ref) => match tails {
                 Some(_) => {
-                    let (dname, _) = self.resolve_from_name(use_ref, imports)?;
+                    let (dname, _) = self.resolve_from_name(use_ref)?;
                     self.resolve_from_name(
-                        Name::new(&dname.get_par_name(false), name.name),
-                        imports,
+                        Name::new(&dname.get_par_name(false), name.name).with_loc(name.loc),
                     )
                 }
-                None => self.resolve_from_name(use_ref, imports),
+                None => self.resolve_from_name(use_ref),
             },
-            _ => Err(PError::from(PErrorKind::NotDefined(name.name)).into()),
+            _ => {
+                let mut err = PError::from(PErrorKind::NotDefined(name.name.clone()))
+                    .with_loc(name.loc.clone());
+                let variables = self.visible_names(name.clone(), DefineKind::is_value);
+                if let Some(similar) = find_similar(&name.name, variables) {
+                    err = err.with_help(&format!("did you mean \"{}\"?", similar));
+                }
+                Err(err.into())
+            }
         }
     }
 
-    // 与えられた関数名に対応する関数を探し，関数に登録されている引数の型の一覧を返す
-    pub fn get_args_type(
+    // 与えられた関数名に対応する関数を探し，関数に登録されている引数(名前, 型)の一覧を返す
+    // ※type_argsが与えられた場合，Moduleの型パラメータはtype_argsに置き換えられる
+    pub fn get_args(
         &self,
         func_name: &Name,
-        imports: &Vec<Name>,
-    ) -> anyhow::Result<Vec<Type>> {
+        type_args: &[Type],
+    ) -> anyhow::Result<Vec<(Name, Type)>> {
+        let type_params = self.get_type_params(&func_name.get_par_name(false));
         let func_name = func_name.get_full_name();
         let mut args = vec![];
-        for Define { kind, refs } in &self.defines {
+        for Define { kind, refs } in self.defines.scope(&func_name) {
             if let DefineKind::Argument(types) = kind {
-                if refs.namespace == func_name {
-                    args.push(
-                        self.resolve_from_type((refs.clone(), types.clone()), imports)?
-                            .1,
-                    );
-                }
+                let (_, types) = self.resolve_from_type((refs.clone(), types.clone()))?;
+                args.push((refs.clone(), instantiate(types, &type_params, type_args)));
             }
         }
         Ok(args)
     }
 
-    // data(Data)内のmember(Member)の定義を探す
+    // 合成元を含むData(data)のメンバの中に，同じ名前のメンバが存在しないかを確認する
+    pub fn check_members(&self, data: &Name) -> anyhow::Result<()> {
+        let mut members: Vec<&Name> = vec![];
+        for (owner, _) in self.get_member_scopes(data, &[], &mut vec![])? {
+            for Define { kind, refs } in self.defines.scope(&owner.get_full_name()) {
+                if !matches!(kind, DefineKind::DataMember(_)) {
+                    continue;
+                }
+                if let Some(first) = members.iter().find(|member| member.name == refs.name) {
+                    let loc = if &owner == data {
+                        refs.loc.clone()
+                    } else {
+                        data.loc.clone()
+                    };
+                    return Err(PError::from(PErrorKind::AlreadyDefined(refs.name.clone()))
+                        .with_loc(loc)
+                        .with_label(first.loc.clone(), "first defined here")
+                        .into());
+                }
+                members.push(refs);
+            }
+        }
+        Ok(())
+    }
+
+    // Data(data)のメンバを探す範囲 (合成元のData, data自身) と，それぞれに与えられた型引数の一覧
+    // ※合成元は宣言順に，再帰的に展開される
+    // ※visitedは展開済みのData (合成が循環している場合に展開を打ち切るため)
+    fn get_member_scopes(
+        &self,
+        data: &Name,
+        type_args: &[Type],
+        visited: &mut Vec<String>,
+    ) -> anyhow::Result<Vec<(Name, Vec<Type>)>> {
+        let data_name = data.get_full_name();
+        if visited.contains(&data_name) {
+            return Ok(vec![]);
+        }
+        visited.push(data_name.clone());
+
+        let mut scopes = vec![];
+        for (name, types) in self.extends.get(&data_name).into_iter().flatten() {
+            let (_, base) = self.resolve_from_type((name.clone(), types.clone()))?;
+            if base.kind != TypeKind::Data {
+                return Err(PError::from(PErrorKind::TypeUnmatch1(types.clone()))
+                    .with_loc(name.loc.clone())
+                    .into());
+            }
+            let base = instantiate(base, &self.get_type_params(data), type_args);
+            scopes.extend(self.get_member_scopes(
+                base.refs.as_ref().unwrap(),
+                &base.params,
+                visited,
+            )?);
+        }
+        scopes.push((data.clone(), type_args.to_vec()));
+        Ok(scopes)
+    }
+
+    // Data, Moduleに定義されている型パラメータの一覧
+    fn get_type_params(&self, refs: &Name) -> Vec<Name> {
+        self.defines
+            .scope(&refs.get_full_name())
+            .filter(|Define { kind, .. }| matches!(kind, DefineKind::TypeParam))
+            .map(|Define { refs, .. }| refs.clone())
+            .collect()
+    }
+
+    // data(Data)内のmember(Member)の定義を探す (合成元のDataのメンバを含む)
+    // ※type_argsはdataに与えられた型引数 (メンバの型に含まれる型パラメータを置き換える)
+    // ※locはエラー発生時に報告する位置 (参照元)
     fn get_member_in_data(
         &self,
         data: &Name,
+        type_args: &[Type],
         member: &str,
-        imports: &Vec<Name>,
+        loc: &Location,
     ) -> anyhow::Result<(Name, Type)> {
         let (head, tails) = split_name(member);
-        for Define { kind, refs } in &self.defines {
-            if let DefineKind::DataMember(types) = kind {
-                if data.get_full_name() == refs.namespace && head == refs.name {
-                    let (_, types) =
-                        self.resolve_from_type((refs.clone(), types.clone()), imports)?;
-                    if types.kind.is_primitive() {
+        let scopes = self.get_member_scopes(data, type_args, &mut vec![])?;
+        for (owner, owner_type_args) in &scopes {
+            for Define { kind, refs } in self.defines.get(&owner.get_full_name(), &head) {
+                if let DefineKind::DataMember(types) = kind {
+                    let (_, types) = self.resolve_from_type((refs.clone(), types.clone()))?;
+                    let types = instantiate(types, &self.get_type_params(owner), owner_type_args);
+                    if types.kind != TypeKind::Data {
                         return match tails {
-                            Some(_) => Err(PError::from(PErrorKind::IllegalAccess).into()),
+                            Some(_) => Err(PError::from(PErrorKind::IllegalAccess)
+                                .with_loc(loc.clone())
+                                .into()),
                             None => Ok((refs.clone(), types)),
                         };
                     }
-                    if types.kind == TypeKind::Data {
-                        return match tails {
-                            Some(tails) => self.get_member_in_data(
-                                types.refs.as_ref().unwrap(),
-                                &tails,
-                                imports,
-                            ),
-                            None => Ok((types.refs.clone().unwrap(), types)),
-                        };
-                    }
-                    panic!("Internal Error");
+                    return match tails {
+                        Some(tails) => self.get_member_in_data(
+                            types.refs.as_ref().unwrap(),
+                            &types.params,
+                            &tails,
+                            loc,
+                        ),
+                        None => Ok((types.refs.clone().unwrap(), types)),
+                    };
                 }
             }
         }
-        Err(PError::from(PErrorKind::MemberNotDefinedInData(
+        let mut err = PError::from(PErrorKind::MemberNotDefinedInData(
             member.to_string(),
             data.name.clone(),
         ))
-        .into())
+        .with_loc(loc.clone());
+        let members = scopes.iter().flat_map(|(owner, _)| {
+            self.defines
+                .scope(&owner.get_full_name())
+                .filter(|Define { kind, .. }| matches!(kind, DefineKind::DataMember(_)))
+                .map(|Define { refs, .. }| refs.name.as_str())
+        });
+        if let Some(similar) = find_similar(&head, members) {
+            err = err.with_help(&format!("did you mean \"{}\"?", similar));
+        }
+        Err(err.into())
     }
 
-    // module(Module)内のfunc(Function)の定義を探す
+    // module(Module, Interface)内のfunc(Function)の定義を探す
+    // ※type_argsはmoduleに与えられた型引数 (返り値の型に含まれる型パラメータを置き換える)
+    // ※locはエラー発生時に報告する位置 (参照元)
     fn get_func_in_module(
         &self,
         module: &Name,
-        func: &String,
-        imports: &Vec<Name>,
+        func: &str,
+        type_args: &[Type],
+        loc: &Location,
     ) -> anyhow::Result<(Name, Type)> {
-        for Define { kind, refs } in &self.defines {
+        let module_name = module.get_full_name();
+        for Define { kind, refs } in self.defines.get(&module_name, func) {
             if let DefineKind::Function(types) = kind {
-                if module == &refs.get_par_name(true) && func == &refs.name {
-                    return Ok((
-                        refs.clone(),
-                        self.resolve_from_type((refs.clone(), types.clone()), imports)?
-                            .1,
-                    ));
-                }
+                let (_, types) = self.resolve_from_type((refs.clone(), types.clone()))?;
+                return Ok((
+                    refs.clone(),
+                    instantiate(types, &self.get_type_params(module), type_args),
+                ));
             }
         }
-        Err(PError::from(PErrorKind::FuncNotDefinedInModule(
-            func.clone(),
+        let mut err = PError::from(PErrorKind::FuncNotDefinedInModule(
+            func.to_string(),
             module.name.clone(),
         ))
-        .into())
+        .with_loc(loc.clone());
+        let funcs = self
+            .defines
+            .scope(&module_name)
+            .filter(|Define { kind, .. }| matches!(kind, DefineKind::Function(_)))
+            .map(|Define { refs, .. }| refs.name.as_str());
+        if let Some(similar) = find_similar(func, funcs) {
+            err = err.with_help(&format!("did you mean \"{}\"?", similar));
+        }
+        Err(err.into())
     }
 
     // namespace内に存在する定義を対象に，nameと同じ名前を持つ定義を探して返す
-    // namespace内に存在しない場合はimports内の名前を探して返す
-    // ※namespaceはルートにたどり着くまで再帰的に更新されながら検索が続く (.a.b.c -> .a.b -> .a -> .)
+    // namespace内に存在しない場合は，namespaceが属するUnitのimportを対象に探して返す
+    // ※namespace内の定義がexpectsを満たさない場合(メンバ time: time.Timestamp の time)も，importを優先する
+    // ※見つからなかった場合のエラーには，expectsを満たす定義の中から似た名前の候補などの提案が付与される
     fn find(
         &self,
-        mut namespace: Name,
+        namespace: Name,
         name: &String,
-        imports: &Vec<Name>,
+        expects: fn(&DefineKind) -> bool,
     ) -> anyhow::Result<Define> {
+        let found_def = match self.find_local_def(namespace.clone(), name) {
+            Some(def) if !expects(&def.kind) => self
+                .find_imported_def(&namespace, name, expects)
+                .or(Some(def)),
+            Some(def) => Some(def),
+            None => self.find_imported_def(&namespace, name, expects),
+        };
+        match found_def {
+            Some(def) => {
+                self.check_visibility(&def.refs, &namespace)?;
+                Ok(def)
+            }
+            None => {
+                let err = PError::from(PErrorKind::NotFound(name.clone()))
+                    .with_loc(namespace.loc.clone());
+                Err(self
+                    .suggest_for_not_found(err, namespace, name, expects)
+                    .into())
+            }
+        }
+    }
+
+    // ※namespaceはルートにたどり着くまで再帰的に更新されながら検索が続く (.a.b.c -> .a.b -> .a -> .)
+    fn find_local_def(&self, mut namespace: Name, name: &str) -> Option<Define> {
         let had_underscore = namespace.has_underscore();
         while !namespace.name.is_empty() {
-            for Define { kind, refs } in &self.defines {
-                if refs.namespace == namespace.namespace && &refs.name == name {
-                    if let DefineKind::Variable(_) = kind {
-                        if had_underscore && !refs.has_underscore() {
-                            continue;
-                        }
+            for Define { kind, refs } in self.defines.get(&namespace.namespace, name) {
+                if let DefineKind::Variable(_) = kind {
+                    if had_underscore && !refs.has_underscore() {
+                        continue;
                     }
-                    return Ok(Define::new(kind.clone(), refs.clone()));
                 }
+                return Some(Define::new(kind.clone(), refs.clone()));
             }
             namespace = namespace.get_par_name(false);
         }
+        None
+    }
 
+    // namespaceが属するUnitのimportを対象に，nameと同じ名前を持つ定義を探して返す
+    // ※名前を指定したimport (from .. import A as B, import std.time) はワイルドカード (from .. import *) より優先される
+    // ※ワイルドカードでは，namespaceから参照可能な定義のみが対象となる
+    fn find_imported_def(
+        &self,
+        namespace: &Name,
+        name: &String,
+        expects: fn(&DefineKind) -> bool,
+    ) -> Option<Define> {
+        let imports = self.imports.get(self.get_unit(namespace)?)?;
+        for import in imports {
+            let found_def = match import {
+                unchecked::SysDCImport::Item { target, alias } if &alias.name == name => {
+                    self.find_local_def(target.clone(), &target.name)
+                }
+                unchecked::SysDCImport::Unit { target, alias } if &alias.name == name => {
+                    Some(Define::new(DefineKind::Unit, target.clone()))
+                }
+                _ => None,
+            };
+            if let Some(def) = found_def.filter(|def| expects(&def.kind)) {
+                return Some(def);
+            }
+        }
         for import in imports {
-            if &import.name == name {
-                return self.find(import.clone(), &import.name, &vec![]);
+            if let unchecked::SysDCImport::Wildcard(target) = import {
+                for Define { kind, refs } in self.defines.get(&target.get_full_name(), name) {
+                    if kind.is_unit_item()
+                        && expects(kind)
+                        && self.check_visibility(refs, namespace).is_ok()
+                    {
+                        return Some(Define::new(kind.clone(), refs.clone()));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Unit(unit)の直下に定義されている，nameと同じ名前を持つ定義を探して返す (time.Time の Time)
+    // ※同じ名前の定義が存在しない場合は，unitの下位のUnitを探す (std.time の time)
+    // ※accessorは参照元 (可視性の確認とエラー発生時に報告する位置に使われる)
+    fn find_in_unit(&self, unit: &Name, accessor: &Name, name: &str) -> anyhow::Result<Define> {
+        let unit_name = unit.get_full_name();
+        for Define { kind, refs } in self.defines.get(&unit_name, name) {
+            if kind.is_unit_item() {
+                self.check_visibility(refs, accessor)?;
+                return Ok(Define::new(kind.clone(), refs.clone()));
             }
         }
 
-        Err(PError::from(PErrorKind::NotFound(name.clone())).into())
+        let nested = Name::new(unit, name.to_string());
+        if self.units.contains(&nested.get_full_name()) {
+            return Ok(Define::new(DefineKind::Unit, nested));
+        }
+
+        let mut err =
+            PError::from(PErrorKind::NotFound(name.to_string())).with_loc(accessor.loc.clone());
+        let items = self
+            .defines
+            .scope(&unit_name)
+            .filter(|Define { kind, refs }| {
+                kind.is_unit_item() && self.check_visibility(refs, accessor).is_ok()
+            })
+            .map(|Define { refs, .. }| refs.name.as_str());
+        if let Some(similar) = find_similar(name, items) {
+            err = err.with_help(&format!("did you mean \"{}\"?", similar));
+        }
+        Err(err.into())
+    }
+
+    // namespaceから参照可能な定義のうち，filterを満たすものの名前の一覧
+    fn visible_names(&self, mut namespace: Name, filter: fn(&DefineKind) -> bool) -> Vec<&str> {
+        let mut names = vec![];
+        while !namespace.name.is_empty() {
+            for Define { kind, refs } in self.defines.scope(&namespace.namespace) {
+                if filter(kind) {
+                    names.push(refs.name.as_str());
+                }
+            }
+            namespace = namespace.get_par_name(false);
+        }
+        names
+    }
+
+    // 見つからなかった名前について，似た名前の候補と，他のUnitに定義されている場合はimportの追加を提案する
+    fn suggest_for_not_found(
+        &self,
+        mut err: PError,
+        namespace: Name,
+        name: &str,
+        expects: fn(&DefineKind) -> bool,
+    ) -> PError {
+        let mut candidates = self.visible_names(namespace.clone(), expects);
+        if expects(&DefineKind::Data) {
+            let imports = self
+                .get_unit(&namespace)
+                .and_then(|unit| self.imports.get(unit))
+                .into_iter()
+                .flatten();
+            candidates.extend(imports.filter_map(|import| match import {
+                unchecked::SysDCImport::Item { alias, .. }
+                | unchecked::SysDCImport::Unit { alias, .. } => Some(alias.name.as_str()),
+                unchecked::SysDCImport::Wildcard(_) => None,
+            }));
+        }
+        if let Some(similar) = find_similar(name, candidates) {
+            err = err.with_help(&format!("did you mean \"{}\"?", similar));
+        }
+
+        for Define { kind, refs } in self.defines.iter() {
+            if kind.is_unit_item()
+                && expects(kind)
+                && refs.name == name
+                && self.check_visibility(refs, &namespace).is_ok()
+            {
+                let unit = refs.namespace.trim_start_matches(".0.");
+                err = err.with_help(&format!(
+                    "\"{}\" is defined in unit \"{}\", consider importing it: `from {} import {};`",
+                    name, unit, unit, name
+                ));
+            }
+        }
+        err
     }
 
     /* ----- ↓前処理用↓ ----- */
 
-    fn define(&mut self, def: Define) -> anyhow::Result<()> {
-        match &self.find(def.refs.clone(), &def.refs.name, &vec![]) {
-            Ok(Define { kind, .. }) => match (kind, &def.kind) {
+    // 多重定義が見つかった場合はエラーを記録し，定義は登録しない
+    fn define(&mut self, def: Define) {
+        if let Some(Define { kind, refs }) = &self.find_local_def(def.refs.clone(), &def.refs.name)
+        {
+            match (kind, &def.kind) {
                 (DefineKind::Argument(_), _) => {}
                 (_, DefineKind::Argument(_)) => {}
-                _ => return Err(PError::from(PErrorKind::AlreadyDefined(def.refs.name)).into()),
-            },
-            Err(_) => {}
+                _ => {
+                    let err = PError::from(PErrorKind::AlreadyDefined(def.refs.name))
+                        .with_loc(def.refs.loc)
+                        .with_label(refs.loc.clone(), "first defined here");
+                    self.errors.push(err.into());
+                    return;
+                }
+            }
         }
         self.defines.push(def);
-        Ok(())
     }
 
-    fn listup_defines(&mut self, system: &unchecked::SysDCSystem) -> anyhow::Result<()> {
+    fn listup_defines(&mut self, system: &unchecked::SysDCSystem) {
         for unit in &system.units {
-            self.listup_defines_unit(unit)?;
+            self.listup_defines_unit(unit);
         }
-        Ok(())
     }
 
-    fn listup_defines_unit(&mut self, unit: &unchecked::SysDCUnit) -> anyhow::Result<()> {
+    fn listup_defines_unit(&mut self, unit: &unchecked::SysDCUnit) {
+        self.units.insert(unit.name.get_full_name());
+        self.listup_imports(unit);
         for data in &unit.data {
-            self.define(Define::new(DefineKind::Data, data.name.clone()))?;
-            self.listup_defines_data(data)?;
+            self.define(Define::new(DefineKind::Data, data.name.clone()));
+            self.set_visibility(&data.name, &data.visibility);
+            self.listup_defines_data(data);
+        }
+        for _enum in &unit.enums {
+            self.define(Define::new(DefineKind::Enum, _enum.name.clone()));
+            self.set_visibility(&_enum.name, &_enum.visibility);
+            self.listup_defines_enum(_enum);
+        }
+        for interface in &unit.interfaces {
+            self.define(Define::new(DefineKind::Interface, interface.name.clone()));
+            self.set_visibility(&interface.name, &interface.visibility);
+            self.listup_defines_interface(interface);
         }
         for module in &unit.modules {
-            self.define(Define::new(DefineKind::Module, module.name.clone()))?;
-            self.listup_defines_module(module)?;
+            self.define(Define::new(DefineKind::Module, module.name.clone()));
+            self.set_visibility(&module.name, &module.visibility);
+            self.listup_defines_module(module);
         }
-        Ok(())
     }
 
-    fn listup_defines_data(&mut self, data: &unchecked::SysDCData) -> anyhow::Result<()> {
+    // 同じUnit内で別名が重複している場合はエラーを記録し，importは登録しない
+    fn listup_imports(&mut self, unit: &unchecked::SysDCUnit) {
+        let unit_name = unit.name.get_full_name();
+        let mut imports = self.imports.remove(&unit_name).unwrap_or_default();
+        for import in &unit.imports {
+            if let unchecked::SysDCImport::Item { alias, .. }
+            | unchecked::SysDCImport::Unit { alias, .. } = import
+            {
+                let first = imports.iter().find_map(|imported| match imported {
+                    unchecked::SysDCImport::Item { alias: first, .. }
+                    | unchecked::SysDCImport::Unit { alias: first, .. }
+                        if first.name == alias.name =>
+                    {
+                        Some(first)
+                    }
+                    _ => None,
+                });
+                if let Some(first) = first {
+                    let err = PError::from(PErrorKind::AlreadyDefined(alias.name.clone()))
+                        .with_loc(alias.loc.clone())
+                        .with_label(first.loc.clone(), "first imported here");
+                    self.errors.push(err.into());
+                    continue;
+                }
+            }
+            imports.push(import.clone());
+        }
+        self.imports.insert(unit_name, imports);
+    }
+
+    fn set_visibility(&mut self, name: &Name, visibility: &Visibility) {
+        self.visibilities
+            .insert(name.get_full_name(), visibility.clone());
+    }
+
+    fn listup_defines_data(&mut self, data: &unchecked::SysDCData) {
+        for param in &data.params {
+            self.define(Define::new(DefineKind::TypeParam, param.clone()));
+        }
+        if !data.extends.is_empty() {
+            self.extends
+                .insert(data.name.get_full_name(), data.extends.clone());
+        }
         for (name, types) in &data.members {
             self.define(Define::new(
                 DefineKind::DataMember(types.clone()),
                 name.clone(),
-            ))?;
+            ));
+        }
+    }
+
+    // ※Variantの持つメンバはDataのメンバと同様に扱う
+    fn listup_defines_enum(&mut self, _enum: &unchecked::SysDCEnum) {
+        for variant in &_enum.variants {
+            self.define(Define::new(DefineKind::Variant, variant.name.clone()));
+            for (name, types) in &variant.members {
+                self.define(Define::new(
+                    DefineKind::DataMember(types.clone()),
+                    name.clone(),
+                ));
+            }
         }
-        Ok(())
     }
 
-    fn listup_defines_module(&mut self, module: &unchecked::SysDCModule) -> anyhow::Result<()> {
+    // ※Interfaceの関数は，Moduleの関数と同様に呼び出せるよう登録する
+    fn listup_defines_interface(&mut self, interface: &unchecked::SysDCInterface) {
+        for func in &interface.functions {
+            self.define(Define::new(
+                DefineKind::Function(func.returns.1.clone()),
+                func.name.clone(),
+            ));
+            self.set_visibility(&func.name, &func.visibility);
+            self.listup_defines_function(func);
+        }
+    }
+
+    fn listup_defines_module(&mut self, module: &unchecked::SysDCModule) {
+        for param in &module.params {
+            self.define(Define::new(DefineKind::TypeParam, param.clone()));
+        }
         for func in &module.functions {
             self.define(Define::new(
                 DefineKind::Function(func.returns.1.clone()),
                 func.name.clone(),
-            ))?;
-            self.listup_defines_function(func)?;
+            ));
+            self.set_visibility(&func.name, &func.visibility);
+            self.listup_defines_function(func);
         }
-        Ok(())
     }
 
-    fn listup_defines_function(&mut self, func: &unchecked::SysDCFunction) -> anyhow::Result<()> {
+    fn listup_defines_function(&mut self, func: &unchecked::SysDCFunction) {
         for (name, types) in &func.args {
             self.define(Define::new(
                 DefineKind::Variable(types.clone()),
                 name.clone(),
-            ))?;
+            ));
             self.define(Define::new(
                 DefineKind::Argument(types.clone()),
                 name.clone(),
-            ))?;
+            ));
         }
         for annotation in &func.annotations {
             if let unchecked::SysDCAnnotation::Spawn {
@@ -312,26 +909,26 @@ impl DefinesManager {
                 self.define(Define::new(
                     DefineKind::Variable(types.clone()),
                     name.clone(),
-                ))?;
-                self.listup_defines_annotation_spawn_details(details)?;
+                ));
+                self.listup_defines_annotation_spawn_details(details);
             }
         }
-        Ok(())
     }
 
     fn listup_defines_annotation_spawn_details(
         &mut self,
         details: &Vec<unchecked::SysDCSpawnDetail>,
-    ) -> anyhow::Result<()> {
+    ) {
         for detail in details {
             match detail {
                 unchecked::SysDCSpawnDetail::Use(name, _) => {
                     let outer_spawn_namespace = name.clone().get_par_name(true);
-                    let outer_use_name = Name::new(&outer_spawn_namespace, name.clone().name);
+                    let outer_use_name = Name::new(&outer_spawn_namespace, name.clone().name)
+                        .with_loc(name.loc.clone());
                     self.define(Define::new(
                         DefineKind::Use(outer_use_name.clone()),
                         name.clone(),
-                    ))?;
+                    ));
                 }
                 unchecked::SysDCSpawnDetail::LetTo {
                     name,
@@ -341,15 +938,55 @@ impl DefinesManager {
                     self.define(Define::new(
                         DefineKind::Variable(func.clone()),
                         name.clone(),
-                    ))?;
+                    ));
                 }
                 _ => {}
             }
         }
-        Ok(())
     }
 }
 
+// type_nameに与えられた型引数(params)の数がparams_lenと一致するかを確認する
+fn check_params_len(
+    name: &Name,
+    type_name: String,
+    params: &[UncheckedType],
+    params_len: usize,
+) -> anyhow::Result<()> {
+    if params.len() == params_len {
+        return Ok(());
+    }
+    Err(PError::from(PErrorKind::TypeParamsLengthNotMatch(
+        type_name,
+        params_len,
+        params.len(),
+    ))
+    .with_loc(name.loc.clone())
+    .into())
+}
+
+// types内の型パラメータ(params)を，対応する型引数(args)に置き換える
+// ※型引数が与えられていない場合(型パラメータを持つ定義の内部からの参照)は置き換えない
+fn instantiate(types: Type, params: &[Name], args: &[Type]) -> Type {
+    if args.is_empty() || params.len() != args.len() {
+        return types;
+    }
+    if types.kind == TypeKind::Param {
+        if let Some(idx) = params
+            .iter()
+            .position(|param| Some(param) == types.refs.as_ref())
+        {
+            return args[idx].clone();
+        }
+    }
+    let type_params = types
+        .params
+        .into_iter()
+        .map(|param| instantiate(param, params, args))
+        .collect();
+    Type::new(types.kind, types.refs).with_params(type_params)
+}
+
 fn split_name(s: &str) -> (String, Option<String>) {
     let splitted = s.split('.').collect::<Vec<&str>>();
     match splitted.len() {
@@ -0,0 +1,86 @@
+use std::collections::{HashMap, HashSet};
+
+// 有向グラフ上の循環を探す
+// ※nodesは探索を行う順に並んだノードの一覧
+// ※edgesはノードごとの，行き先のノードとその辺に付与された情報(位置など)の一覧
+// ※見つかった循環はそれぞれ，循環を構成するノードとそこから出る辺の情報の列として返される
+pub fn find_cycles<'a, E>(
+    nodes: &'a [String],
+    edges: &'a HashMap<String, Vec<(String, E)>>,
+) -> Vec<Vec<(&'a String, &'a E)>> {
+    let mut finder = CycleFinder {
+        edges,
+        finished: HashSet::new(),
+        cycles: vec![],
+    };
+    for node in nodes {
+        finder.visit(node, &mut vec![]);
+    }
+    finder.cycles
+}
+
+// ※finishedは探索を終えたノード (そこから先の循環は報告済み)
+struct CycleFinder<'a, E> {
+    edges: &'a HashMap<String, Vec<(String, E)>>,
+    finished: HashSet<&'a String>,
+    cycles: Vec<Vec<(&'a String, &'a E)>>,
+}
+
+impl<'a, E> CycleFinder<'a, E> {
+    // ※pathは探索中のノードと，その次のノードへの辺の情報の一覧
+    fn visit(&mut self, node: &'a String, path: &mut Vec<(&'a String, &'a E)>) {
+        if let Some(idx) = path.iter().position(|(visiting, _)| *visiting == node) {
+            self.cycles.push(path[idx..].to_vec());
+            return;
+        }
+        if self.finished.contains(node) {
+            return;
+        }
+
+        for (to, info) in self.edges.get(node).into_iter().flatten() {
+            path.push((node, info));
+            self.visit(to, path);
+            path.pop();
+        }
+        self.finished.insert(node);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::find_cycles;
+
+    #[test]
+    fn cycles() {
+        let nodes = ["a", "b", "c", "d"].map(String::from).to_vec();
+        let edges = HashMap::from([
+            ("a".to_string(), vec![("b".to_string(), 1)]),
+            (
+                "b".to_string(),
+                vec![("c".to_string(), 2), ("d".to_string(), 3)],
+            ),
+            ("c".to_string(), vec![("a".to_string(), 4)]),
+            ("d".to_string(), vec![("d".to_string(), 5)]),
+        ]);
+        let cycles = find_cycles(&nodes, &edges)
+            .into_iter()
+            .map(|cycle| {
+                cycle
+                    .into_iter()
+                    .map(|(node, info)| format!("{}{}", node, info))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(cycles, vec!["a1 b2 c4", "d5"]);
+    }
+
+    #[test]
+    fn no_cycles() {
+        let nodes = ["a", "b"].map(String::from).to_vec();
+        let edges = HashMap::from([("a".to_string(), vec![("b".to_string(), ())])]);
+        assert!(find_cycles(&nodes, &edges).is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+// 2つの文字列の編集距離
+// ※挿入, 削除, 置換に加えて，隣り合う文字の入れ替えも1回の操作として数える
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    dist[a.len()][b.len()]
+}
+
+// candidatesの中から，nameに最も近い名前を返す
+// ※十分に近い(名前の長さの1/3程度までの違いしかない)ものが無い場合はNone
+pub fn find_similar<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let threshold = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(dist, _)| *dist <= threshold)
+        .min_by_key(|(dist, _)| *dist)
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{edit_distance, find_similar};
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Box", "Bxo"), 1);
+        assert_eq!(edit_distance("value", "valeu"), 1);
+    }
+
+    #[test]
+    fn similar() {
+        let candidates = vec!["Box", "Boxes", "Timestamp"];
+        assert_eq!(find_similar("Bax", candidates.clone()), Some("Box"));
+        assert_eq!(
+            find_similar("TimeStamp", candidates.clone()),
+            Some("Timestamp")
+        );
+        assert_eq!(find_similar("Logger", candidates.clone()), None);
+        assert_eq!(find_similar("Box", candidates), None);
+    }
+}
@@ -1,27 +1,36 @@
+pub mod explain;
+pub mod render;
+
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 use super::location::Location;
 use super::token::TokenKind;
-use super::types::Type;
+use super::types::{unchecked, Type};
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub enum PErrorKind {
     /* トークン分割時に発生したエラー */
     #[error("Token \"{0:?}\" is requested, but not found")]
     RequestedTokenNotFound(TokenKind),
     #[error("Found unregistered symbol")]
     FoundUnregisteredSymbol,
+    #[error("Comment is not terminated")]
+    UnterminatedComment,
+    #[error("Unexpected character '{0}' found")]
+    UnexpectedCharacter(char),
 
     /* パース時に発生したエラー */
     #[error("Unit name is not specified")]
     UnitNameNotSpecified,
     #[error("From namespace is not specified")]
     FromNamespaceNotSpecified,
-    #[error("Expected Data or Module definition, but not found")]
-    DataOrModuleNotFound,
+    #[error("Expected import, rule, data, enum, interface or module, but not found")]
+    SentenceNotFound,
     #[error("Unexpected EOF found")]
     UnexpectedEOF,
     #[error("Annotation \"return\" exists multiple")]
@@ -40,10 +49,10 @@ pub enum PErrorKind {
     /* 検査時に発生したエラー */
     #[error("\"{0}\" is already defiend")]
     AlreadyDefined(String),
-    #[error("\"{0:?}\" is defined, but type is mismatch")]
-    TypeUnmatch1(Type),
-    #[error("\"{0:?}\" is required, but \"{1:?}\" found")]
-    TypeUnmatch2(Type, Type),
+    #[error("\"{0}\" is defined, but type is mismatch")]
+    TypeUnmatch1(unchecked::Type),
+    #[error("\"{0}\" is required, but \"{1}\" found")]
+    TypeUnmatch2(Box<Type>, Box<Type>),
     #[error("Argument length not match")]
     ArgumentsLengthNotMatch,
     #[error("Cannot find \"{0}\"")]
@@ -58,12 +67,103 @@ pub enum PErrorKind {
     MissingFunctionName,
     #[error("Found illegal access")]
     IllegalAccess,
+    #[error("Type \"{0}\" takes {1} type parameter(s), but {2} given")]
+    TypeParamsLengthNotMatch(String, usize, usize),
+    #[error("Function \"{0}\" of Interface \"{1}\" is not implemented")]
+    FuncNotImplemented(String, String),
+    #[error("Function \"{0}\" does not match the signature declared in Interface \"{1}\"")]
+    SignatureUnmatch(String, String),
+    #[error("\"{0}\" is not an interface")]
+    NotInterface(String),
+    #[error("\"{0}\" is private")]
+    PrivateItem(String),
+    #[error("Unit \"{0}\" must not depend on unit \"{1}\"")]
+    ForbiddenDependency(String, String),
+    #[error("Found recursive data without indirection: {0}")]
+    RecursiveData(String),
+    #[error("\"{0}\" cannot be modified, because it is not a Data")]
+    ModifyTargetNotData(String),
+
+    /* 処理系の不具合によって発生したエラー */
+    #[error("Internal error: {0}")]
+    InternalError(String),
+
+    /* Lint時に発生した警告 */
+    #[error("Argument \"{0}\" is never used")]
+    UnusedArgument(String),
+    #[error("Variable \"{0}\" is never used")]
+    UnusedVariable(String),
+    #[error("Imported \"{0}\" is never used")]
+    UnusedImport(String),
+    #[error("Data \"{0}\" has no members")]
+    EmptyData(String),
+    #[error("Module \"{0}\" has no functions")]
+    EmptyModule(String),
+    #[error("Found import cycle: {0}")]
+    ImportCycle(String),
+    #[error("\"{0}\" is a func, which has no side effects to affect")]
+    AffectOnFunc(String),
+}
+
+impl PErrorKind {
+    // エラーの種類ごとに割り当てられた識別子
+    // ※外部ツールから参照されるため，一度割り当てた値は変更しないこと
+    pub fn code(&self) -> &'static str {
+        match self {
+            PErrorKind::RequestedTokenNotFound(..) => "E0001",
+            PErrorKind::FoundUnregisteredSymbol => "E0002",
+            PErrorKind::UnitNameNotSpecified => "E0003",
+            PErrorKind::FromNamespaceNotSpecified => "E0004",
+            PErrorKind::SentenceNotFound => "E0005",
+            PErrorKind::UnexpectedEOF => "E0006",
+            PErrorKind::ReturnExistsMultiple => "E0007",
+            PErrorKind::ReturnExistsOnProcedure => "E0008",
+            PErrorKind::ReturnNotExists => "E0009",
+            PErrorKind::ResultOfSpawnNotSpecified => "E0010",
+            PErrorKind::FunctionNameNotFound => "E0011",
+            PErrorKind::UnknownAnnotationFound(..) => "E0012",
+            PErrorKind::AlreadyDefined(..) => "E0013",
+            PErrorKind::TypeUnmatch1(..) => "E0014",
+            PErrorKind::TypeUnmatch2(..) => "E0015",
+            PErrorKind::ArgumentsLengthNotMatch => "E0016",
+            PErrorKind::NotFound(..) => "E0017",
+            PErrorKind::NotDefined(..) => "E0018",
+            PErrorKind::MemberNotDefinedInData(..) => "E0019",
+            PErrorKind::FuncNotDefinedInModule(..) => "E0020",
+            PErrorKind::MissingFunctionName => "E0021",
+            PErrorKind::IllegalAccess => "E0022",
+            PErrorKind::TypeParamsLengthNotMatch(..) => "E0023",
+            PErrorKind::FuncNotImplemented(..) => "E0024",
+            PErrorKind::SignatureUnmatch(..) => "E0025",
+            PErrorKind::NotInterface(..) => "E0026",
+            PErrorKind::UnterminatedComment => "E0027",
+            PErrorKind::UnexpectedCharacter(..) => "E0028",
+            PErrorKind::PrivateItem(..) => "E0029",
+            PErrorKind::ForbiddenDependency(..) => "E0030",
+            PErrorKind::RecursiveData(..) => "E0031",
+            PErrorKind::ModifyTargetNotData(..) => "E0032",
+            PErrorKind::InternalError(..) => "E0033",
+            PErrorKind::UnusedArgument(..) => "W0001",
+            PErrorKind::UnusedVariable(..) => "W0002",
+            PErrorKind::UnusedImport(..) => "W0003",
+            PErrorKind::EmptyData(..) => "W0004",
+            PErrorKind::EmptyModule(..) => "W0005",
+            PErrorKind::ImportCycle(..) => "W0006",
+            PErrorKind::AffectOnFunc(..) => "W0007",
+        }
+    }
 }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, Error)]
 pub struct PError {
     kind: PErrorKind,
     happen_at: Location,
+
+    // エラーに関連する位置とその説明 (例: 多重定義における最初の定義位置)
+    labels: Vec<(Location, String)>,
+
+    // エラーの解消方法の提案 (例: 名前の候補)
+    helps: Vec<String>,
 }
 
 impl From<PErrorKind> for PError {
@@ -71,13 +171,21 @@ impl From<PErrorKind> for PError {
         PError {
             kind,
             happen_at: Location::new(),
+            labels: vec![],
+            helps: vec![],
         }
     }
 }
 
 impl Display for PError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{} (at {})", self.kind, self.happen_at)
+        write!(
+            f,
+            "{}: {} (at {})",
+            self.kind.code(),
+            self.kind,
+            self.happen_at
+        )
     }
 }
 
@@ -86,4 +194,150 @@ impl PError {
         self.happen_at = location;
         self
     }
+
+    pub fn with_label(mut self, location: Location, message: &str) -> PError {
+        self.labels.push((location, message.to_string()));
+        self
+    }
+
+    pub fn with_help(mut self, message: &str) -> PError {
+        self.helps.push(message.to_string());
+        self
+    }
+
+    pub fn kind(&self) -> &PErrorKind {
+        &self.kind
+    }
+
+    pub fn location(&self) -> &Location {
+        &self.happen_at
+    }
+
+    pub fn labels(&self) -> &Vec<(Location, String)> {
+        &self.labels
+    }
+
+    pub fn helps(&self) -> &Vec<String> {
+        &self.helps
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "ERROR"),
+            Severity::Warning => write!(f, "WARNING"),
+        }
+    }
+}
+
+// パース・検査の結果として報告される診断情報
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub error: PError,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, error: PError) -> Diagnostic {
+        Diagnostic { severity, error }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+// ※PError以外のエラーは処理系の不具合として，InternalErrorに包んで報告する
+impl From<anyhow::Error> for Diagnostic {
+    fn from(err: anyhow::Error) -> Diagnostic {
+        match err.downcast::<PError>() {
+            Ok(err) => Diagnostic::new(Severity::Error, err),
+            Err(err) => {
+                let err = PError::from(PErrorKind::InternalError(err.to_string()));
+                Diagnostic::new(Severity::Error, err)
+            }
+        }
+    }
+}
+
+// 外部ツール向けの出力形式
+// {"severity", "code", "message", "location", "labels": [{"location", "message"}], "helps"}
+impl Serialize for Diagnostic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Label<'a> {
+            location: &'a Location,
+            message: &'a str,
+        }
+
+        let labels = self
+            .error
+            .labels
+            .iter()
+            .map(|(location, message)| Label { location, message })
+            .collect::<Vec<Label>>();
+
+        let mut state = serializer.serialize_struct("Diagnostic", 6)?;
+        state.serialize_field("severity", &self.severity)?;
+        state.serialize_field("code", self.error.kind.code())?;
+        state.serialize_field("message", &self.error.kind.to_string())?;
+        state.serialize_field("location", &self.error.happen_at)?;
+        state.serialize_field("labels", &labels)?;
+        state.serialize_field("helps", &self.error.helps)?;
+        state.end()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.severity, self.error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Diagnostic, PError, PErrorKind, Severity};
+    use crate::location::Location;
+
+    #[test]
+    fn diagnostic_from_other_error() {
+        let diagnostic = Diagnostic::from(anyhow::anyhow!("unexpected"));
+        assert!(diagnostic.is_error());
+        assert_eq!(diagnostic.error.kind().code(), "E0033");
+        assert_eq!(
+            format!("{}", diagnostic.error.kind()),
+            "Internal error: unexpected"
+        );
+    }
+
+    #[test]
+    fn serialize_diagnostic() {
+        let error = PError::from(PErrorKind::AlreadyDefined("A".to_string()))
+            .with_loc(
+                Location::new()
+                    .with_filename("test.def".to_string())
+                    .with_coord((4, 6))
+                    .with_end_coord((4, 7)),
+            )
+            .with_label(
+                Location::new()
+                    .with_filename("test.def".to_string())
+                    .with_coord((3, 6))
+                    .with_end_coord((3, 7)),
+                "first defined here",
+            );
+        let diagnostic = Diagnostic::new(Severity::Error, error);
+        assert_eq!(
+            serde_json::to_string(&diagnostic).unwrap(),
+            r#"{"severity":"error","code":"E0013","message":"\"A\" is already defiend","location":{"filename":"test.def","begin":[4,6],"end":[4,7]},"labels":[{"location":{"filename":"test.def","begin":[3,6],"end":[3,7]},"message":"first defined here"}],"helps":[]}"#
+        );
+    }
 }
@@ -0,0 +1,128 @@
+// エラーコードに対応する詳細な説明 (Markdown形式) を返す
+// ※説明には原則として，エラーとなるコード例と修正後のコード例が含まれる
+pub fn explain(code: &str) -> Option<&'static str> {
+    let explanation = match code.to_uppercase().as_str() {
+        "E0001" => include_str!("explain/E0001.md"),
+        "E0002" => include_str!("explain/E0002.md"),
+        "E0003" => include_str!("explain/E0003.md"),
+        "E0004" => include_str!("explain/E0004.md"),
+        "E0005" => include_str!("explain/E0005.md"),
+        "E0006" => include_str!("explain/E0006.md"),
+        "E0007" => include_str!("explain/E0007.md"),
+        "E0008" => include_str!("explain/E0008.md"),
+        "E0009" => include_str!("explain/E0009.md"),
+        "E0010" => include_str!("explain/E0010.md"),
+        "E0011" => include_str!("explain/E0011.md"),
+        "E0012" => include_str!("explain/E0012.md"),
+        "E0013" => include_str!("explain/E0013.md"),
+        "E0014" => include_str!("explain/E0014.md"),
+        "E0015" => include_str!("explain/E0015.md"),
+        "E0016" => include_str!("explain/E0016.md"),
+        "E0017" => include_str!("explain/E0017.md"),
+        "E0018" => include_str!("explain/E0018.md"),
+        "E0019" => include_str!("explain/E0019.md"),
+        "E0020" => include_str!("explain/E0020.md"),
+        "E0021" => include_str!("explain/E0021.md"),
+        "E0022" => include_str!("explain/E0022.md"),
+        "E0023" => include_str!("explain/E0023.md"),
+        "E0024" => include_str!("explain/E0024.md"),
+        "E0025" => include_str!("explain/E0025.md"),
+        "E0026" => include_str!("explain/E0026.md"),
+        "E0027" => include_str!("explain/E0027.md"),
+        "E0028" => include_str!("explain/E0028.md"),
+        "E0029" => include_str!("explain/E0029.md"),
+        "E0030" => include_str!("explain/E0030.md"),
+        "E0031" => include_str!("explain/E0031.md"),
+        "E0032" => include_str!("explain/E0032.md"),
+        "E0033" => include_str!("explain/E0033.md"),
+        "W0001" => include_str!("explain/W0001.md"),
+        "W0002" => include_str!("explain/W0002.md"),
+        "W0003" => include_str!("explain/W0003.md"),
+        "W0004" => include_str!("explain/W0004.md"),
+        "W0005" => include_str!("explain/W0005.md"),
+        "W0006" => include_str!("explain/W0006.md"),
+        "W0007" => include_str!("explain/W0007.md"),
+        _ => return None,
+    };
+    Some(explanation)
+}
+
+#[cfg(test)]
+mod test {
+    use super::explain;
+    use crate::Parser;
+
+    #[test]
+    fn all_codes_are_explained() {
+        for code in codes() {
+            assert!(explain(&code).is_some());
+        }
+        assert!(explain("e0001").is_some());
+        assert!(explain("E9999").is_none());
+    }
+
+    // エラーとなるコード例ではそのエラーが報告され，修正後のコード例では報告されないことを確認する
+    // ※1つの節に複数のコード例がある場合，それらは別々のUnitとしてまとめて検査される
+    #[test]
+    fn examples_are_correct() {
+        for code in codes() {
+            let (erroneous, fixed) = extract_examples(explain(&code).unwrap());
+            if erroneous.is_empty() {
+                continue;
+            }
+
+            let mut parser = Parser::default();
+            for (idx, example) in erroneous.iter().enumerate() {
+                let filename = format!("explain{}.def", idx);
+                let _ = parser.parse(filename, example);
+            }
+            let (_, diagnostics) = parser.check_with_diagnostics();
+            assert!(
+                diagnostics
+                    .iter()
+                    .any(|diagnostic| diagnostic.error.kind().code() == code),
+                "{}",
+                code
+            );
+
+            let mut parser = Parser::default();
+            for (idx, example) in fixed.iter().enumerate() {
+                let filename = format!("explain{}.def", idx);
+                parser.parse(filename, example).unwrap();
+            }
+            let (system, diagnostics) = parser.check_with_diagnostics();
+            assert!(system.is_some(), "{}", code);
+            assert!(diagnostics
+                .iter()
+                .all(|diagnostic| diagnostic.error.kind().code() != code));
+        }
+    }
+
+    fn codes() -> Vec<String> {
+        let errors = (1..=33).map(|idx| format!("E{:04}", idx));
+        let warnings = (1..=7).map(|idx| format!("W{:04}", idx));
+        errors.chain(warnings).collect()
+    }
+
+    // エラーとなるコード例と，修正後のコード例の一覧
+    fn extract_examples(explanation: &str) -> (Vec<String>, Vec<String>) {
+        let (mut erroneous, mut fixed) = (vec![], vec![]);
+        let mut in_fixed = false;
+        let mut example: Option<Vec<&str>> = None;
+        for line in explanation.lines() {
+            match (line, &mut example) {
+                ("## Erroneous code example", None) => in_fixed = false,
+                ("## Fixed code example", None) => in_fixed = true,
+                ("```def", None) => example = Some(vec![]),
+                ("```", Some(lines)) => {
+                    let examples = if in_fixed { &mut fixed } else { &mut erroneous };
+                    examples.push(lines.join("\n"));
+                    example = None;
+                }
+                (line, Some(lines)) => lines.push(line),
+                _ => {}
+            }
+        }
+        (erroneous, fixed)
+    }
+}
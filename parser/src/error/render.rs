@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use unicode_width::UnicodeWidthChar;
+
+use super::{Diagnostic, Severity};
+use crate::location::Location;
+
+// ANSIエスケープシーケンス
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+// 診断情報を，該当箇所のソースコードと下線付きで整形する
+// ※sourcesはファイル名とその内容の対応
+#[derive(Debug, Clone, Default)]
+pub struct Renderer {
+    sources: HashMap<String, String>,
+    color: bool,
+}
+
+impl Renderer {
+    pub fn new(sources: HashMap<String, String>) -> Renderer {
+        Renderer {
+            sources,
+            color: false,
+        }
+    }
+
+    pub fn with_color(mut self, color: bool) -> Renderer {
+        self.color = color;
+        self
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let error = &diagnostic.error;
+        let style = match diagnostic.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        };
+
+        // 表示する箇所の一覧 (最初の要素が主な箇所)
+        let mut snippets = vec![(error.location(), '^', None, style)];
+        for (loc, message) in error.labels() {
+            snippets.push((loc, '-', Some(message.as_str()), BLUE));
+        }
+        let width = snippets
+            .iter()
+            .filter_map(|(loc, ..)| loc.end.or(loc.begin))
+            .map(|(row, _)| row.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let pad = " ".repeat(width);
+
+        // [ERROR] code: message
+        let mut lines = vec![format!(
+            "{} {}",
+            self.paint(style, &format!("[{}]", diagnostic.severity)),
+            self.paint(BOLD, &format!("{}: {}", error.kind().code(), error.kind()))
+        )];
+        if error.location().is_empty() {
+            return lines.join("\n");
+        }
+
+        let mut filename = None;
+        for (idx, (loc, marker, message, style)) in snippets.into_iter().enumerate() {
+            let snippet = self.render_snippet(loc, marker, style, &pad);
+
+            // --> file:row:col (関連箇所のファイルが変わる場合は ::: file:row:col)
+            if idx == 0 || (snippet.is_some() && loc.filename != filename) {
+                let arrow = if idx == 0 { "-->" } else { ":::" };
+                lines.push(format!("{}{} {}", pad, self.paint(BLUE, arrow), loc));
+                filename = loc.filename.clone();
+            }
+
+            match (snippet, message) {
+                (Some(snippet), message) => {
+                    lines.push(format!("{} {}", pad, self.paint(BLUE, "|")));
+                    lines.extend(snippet);
+                    if let Some(message) = message {
+                        let last = lines.pop().unwrap();
+                        lines.push(format!("{} {}", last, self.paint(style, message)));
+                    }
+                }
+                (None, Some(message)) => {
+                    let note = format!("{} (at {})", message, loc);
+                    lines.push(format!("{} {} {}", pad, self.paint(BLUE, "="), note));
+                }
+                (None, None) => {}
+            }
+        }
+
+        // = help: ...
+        for help in error.helps() {
+            lines.push(format!("{} {} help: {}", pad, self.paint(BLUE, "="), help));
+        }
+
+        lines.join("\n")
+    }
+
+    // locの範囲に含まれる各行と，その下線を返す
+    // ※ソースコードが見つからない場合はNone
+    fn render_snippet(
+        &self,
+        loc: &Location,
+        marker: char,
+        style: &str,
+        pad: &str,
+    ) -> Option<Vec<String>> {
+        let source = self.sources.get(loc.filename.as_ref()?)?;
+        let (begin_row, begin_col) = loc.begin?;
+        let (end_row, end_col) = loc.end.unwrap_or((begin_row, begin_col + 1));
+
+        let mut lines = vec![];
+        for row in begin_row..=end_row {
+            let text = source.lines().nth((row - 1) as usize)?.trim_end();
+            let chars = text.chars().collect::<Vec<char>>();
+
+            // 範囲の開始列, 終了列 (終了列は範囲の直後)
+            let first_col = match row == begin_row {
+                true => begin_col,
+                false => chars.iter().take_while(|c| c.is_whitespace()).count() as i32 + 1,
+            };
+            let last_col = match row == end_row {
+                true => end_col,
+                false => chars.len() as i32 + 1,
+            };
+            let last_col = last_col.max(first_col + 1);
+
+            // タブ文字の幅が揃うよう，インデントには元の文字を使う
+            // ※全角文字などの幅が揃うよう，インデントと下線は表示幅に合わせる
+            let indent = chars
+                .iter()
+                .take((first_col - 1).max(0) as usize)
+                .map(|c| match c {
+                    '\t' => "\t".to_string(),
+                    c => " ".repeat(c.width().unwrap_or(0)),
+                })
+                .collect::<String>();
+            let underline_width = (first_col..last_col)
+                .map(|col| match chars.get((col - 1).max(0) as usize) {
+                    Some(c) => c.width().unwrap_or(1),
+                    None => 1,
+                })
+                .sum::<usize>()
+                .max(1);
+            let underline = marker.to_string().repeat(underline_width);
+
+            let gutter = format!("{:>width$} |", row, width = pad.len());
+            lines.push(format!("{} {}", self.paint(BLUE, &gutter), text));
+            lines.push(format!(
+                "{} {}{}",
+                self.paint(BLUE, &format!("{} |", pad)),
+                indent,
+                self.paint(style, &underline)
+            ));
+        }
+        Some(lines)
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        match self.color {
+            true => format!("{}{}{}", style, text, RESET),
+            false => text.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::Renderer;
+    use crate::error::{Diagnostic, PError, PErrorKind, Severity};
+    use crate::location::Location;
+
+    #[test]
+    fn single_line() {
+        let error = PError::from(PErrorKind::NotFound("B".to_string()))
+            .with_loc(generate_loc((4, 8), (4, 9)));
+        assert_eq!(
+            render(error),
+            "\
+[ERROR] E0017: Cannot find \"B\"
+ --> test.def:4:8
+  |
+4 |     x: B,
+  |        ^"
+        );
+    }
+
+    #[test]
+    fn with_label() {
+        let error = PError::from(PErrorKind::AlreadyDefined("A".to_string()))
+            .with_loc(generate_loc((7, 6), (7, 7)))
+            .with_label(generate_loc((3, 6), (3, 7)), "first defined here");
+        assert_eq!(
+            render(error),
+            "\
+[ERROR] E0013: \"A\" is already defiend
+ --> test.def:7:6
+  |
+7 | data A {}
+  |      ^
+  |
+3 | data A {
+  |      - first defined here"
+        );
+    }
+
+    #[test]
+    fn multiple_lines() {
+        let error =
+            PError::from(PErrorKind::SentenceNotFound).with_loc(generate_loc((3, 1), (5, 2)));
+        assert_eq!(
+            render(error),
+            "\
+[ERROR] E0005: Expected import, rule, data, enum, interface or module, but not found
+ --> test.def:3:1
+  |
+3 | data A {
+  | ^^^^^^^^
+4 |     x: B,
+  |     ^^^^^
+5 | }
+  | ^"
+        );
+    }
+
+    #[test]
+    fn with_help() {
+        let error = PError::from(PErrorKind::NotFound("B".to_string()))
+            .with_loc(generate_loc((4, 8), (4, 9)))
+            .with_help("did you mean \"A\"?");
+        assert_eq!(
+            render(error),
+            "\
+[ERROR] E0017: Cannot find \"B\"
+ --> test.def:4:8
+  |
+4 |     x: B,
+  |        ^
+  = help: did you mean \"A\"?"
+        );
+    }
+
+    #[test]
+    fn wide_characters() {
+        let program = "unit test;\n\ndata 利用者 {\n    名前: 文字列,\n}\n";
+        let sources = HashMap::from([("test.def".to_string(), program.to_string())]);
+        let error = PError::from(PErrorKind::NotFound("文字列".to_string()))
+            .with_loc(generate_loc((4, 9), (4, 12)));
+        assert_eq!(
+            Renderer::new(sources).render(&Diagnostic::new(Severity::Error, error)),
+            "\
+[ERROR] E0017: Cannot find \"文字列\"
+ --> test.def:4:9
+  |
+4 |     名前: 文字列,
+  |           ^^^^^^"
+        );
+    }
+
+    #[test]
+    fn without_source() {
+        let error = PError::from(PErrorKind::NotFound("B".to_string()))
+            .with_loc(
+                Location::new()
+                    .with_filename("unknown.def".to_string())
+                    .with_coord((1, 1)),
+            )
+            .with_label(Location::new(), "defined here");
+        assert_eq!(
+            render(error),
+            "\
+[ERROR] E0017: Cannot find \"B\"
+ --> unknown.def:1:1
+  = defined here (at ?)"
+        );
+    }
+
+    fn generate_loc(begin: (i32, i32), end: (i32, i32)) -> Location {
+        Location::new()
+            .with_filename("test.def".to_string())
+            .with_coord(begin)
+            .with_end_coord(end)
+    }
+
+    fn render(error: PError) -> String {
+        let program = "unit test;\n\ndata A {\n    x: B,\n}\n\ndata A {}\n";
+        let sources = HashMap::from([("test.def".to_string(), program.to_string())]);
+        Renderer::new(sources).render(&Diagnostic::new(Severity::Error, error))
+    }
+}
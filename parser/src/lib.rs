@@ -1,12 +1,18 @@
 mod parse;
 mod token;
 mod check;
-mod error;
-mod location;
+pub mod error;
+pub mod lint;
+pub mod location;
 pub mod name;
 pub mod types;
 pub mod structure;
 
+use std::collections::HashMap;
+
+use error::render::Renderer;
+use error::Diagnostic;
+use lint::{Lint, LintConfig};
 use parse::UnitParser;
 use structure::unchecked;
 use structure::SysDCSystem;
@@ -15,18 +21,92 @@ use token::Tokenizer;
 #[derive(Default)]
 pub struct Parser {
     units: Vec<unchecked::SysDCUnit>,
+    diagnostics: Vec<Diagnostic>,
+
+    // 診断情報の表示用に保持しておく，ファイル名とその内容の対応
+    sources: HashMap<String, String>,
+
+    lint_config: LintConfig,
 }
 
 impl Parser {
+    pub fn with_lint_config(mut self, lint_config: LintConfig) -> Parser {
+        self.lint_config = lint_config;
+        self
+    }
+
+    // 見つかったエラーは全て記録され，戻り値としてはそのうち最初のものが返される
     pub fn parse(&mut self, filename: String, program: &str) -> anyhow::Result<()> {
+        self.sources.insert(filename.clone(), program.to_string());
         let tokenizer = Tokenizer::new(filename, program);
-        let system = UnitParser::parse(tokenizer)?;
-        self.units.push(system);
-        Ok(())
+        match UnitParser::parse(tokenizer) {
+            Ok(unit) => {
+                self.units.push(unit);
+                Ok(())
+            }
+            Err(errors) => {
+                let diagnostics = errors
+                    .into_iter()
+                    .map(Diagnostic::from)
+                    .collect::<Vec<Diagnostic>>();
+                let first = diagnostics[0].error.clone();
+                self.diagnostics.extend(diagnostics);
+                Err(first.into())
+            }
+        }
+    }
+
+    // これまでに読み込んだソースコードを参照して診断情報を整形するRendererを返す
+    pub fn renderer(&self) -> Renderer {
+        Renderer::new(self.sources.clone())
     }
 
     pub fn check(self) -> anyhow::Result<SysDCSystem> {
+        match self.check_with_diagnostics() {
+            (Some(system), _) => Ok(system),
+            (None, diagnostics) => {
+                let first = diagnostics.into_iter().find(Diagnostic::is_error).unwrap();
+                Err(first.error.into())
+            }
+        }
+    }
+
+    // パース時のものも含め，見つかった全ての診断情報を返す
+    // ※パース時にエラーが見つかっていた場合は検査を行わない
+    pub fn check_with_diagnostics(self) -> (Option<SysDCSystem>, Vec<Diagnostic>) {
+        let mut diagnostics = self.diagnostics;
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            return (None, diagnostics);
+        }
+
+        let imports = self
+            .units
+            .iter()
+            .map(|unit| unit.imports.clone())
+            .collect::<Vec<_>>();
         let system = unchecked::SysDCSystem::new(self.units);
-        check::check(system)
+        // Unit間のimportの循環は，検査の成否に関わらず報告する
+        if let Some(severity) = self.lint_config.severity(Lint::ImportCycle) {
+            diagnostics.extend(
+                check::find_import_cycles(&system)
+                    .into_iter()
+                    .map(|cycle| Diagnostic::new(severity, cycle)),
+            );
+        }
+        match check::check(system) {
+            Ok(system) => {
+                // 検査に成功した場合のみ，Lintによる警告を追加する
+                // ※Denyが設定されたLintが報告された場合は失敗とする
+                diagnostics.extend(lint::lint(&system, &imports, &self.lint_config));
+                if diagnostics.iter().any(Diagnostic::is_error) {
+                    return (None, diagnostics);
+                }
+                (Some(system), diagnostics)
+            }
+            Err(errors) => {
+                diagnostics.extend(errors.into_iter().map(Diagnostic::from));
+                (None, diagnostics)
+            }
+        }
     }
 }
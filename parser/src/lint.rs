@@ -0,0 +1,815 @@
+use std::collections::HashMap;
+
+use super::error::{Diagnostic, PError, PErrorKind, Severity};
+use super::name::Name;
+use super::structure::unchecked::SysDCImport;
+use super::structure::{
+    FunctionKind, SysDCAnnotation, SysDCData, SysDCFunction, SysDCModule, SysDCSpawnDetail,
+    SysDCSystem, SysDCUnit,
+};
+use super::types::{Type, TypeKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    UnusedArgument,
+    UnusedVariable,
+    UnusedImport,
+    EmptyData,
+    EmptyModule,
+    ImportCycle,
+    AffectFunc,
+}
+
+impl Lint {
+    pub fn all() -> [Lint; 7] {
+        [
+            Lint::UnusedArgument,
+            Lint::UnusedVariable,
+            Lint::UnusedImport,
+            Lint::EmptyData,
+            Lint::EmptyModule,
+            Lint::ImportCycle,
+            Lint::AffectFunc,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedArgument => "unused_argument",
+            Lint::UnusedVariable => "unused_variable",
+            Lint::UnusedImport => "unused_import",
+            Lint::EmptyData => "empty_data",
+            Lint::EmptyModule => "empty_module",
+            Lint::ImportCycle => "import_cycle",
+            Lint::AffectFunc => "affect_func",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::all().into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+// Lintに該当する記述の扱い
+// ※Allowは報告しない，Warnは警告，Denyはエラーとして報告する
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+// Lintごとの扱いの設定 (デフォルトではすべてWarn)
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: Vec<(Lint, LintLevel)>,
+}
+
+impl LintConfig {
+    pub fn new() -> LintConfig {
+        LintConfig { levels: vec![] }
+    }
+
+    pub fn with_lint_level(mut self, lint: Lint, level: LintLevel) -> LintConfig {
+        self.levels.retain(|(configured, _)| configured != &lint);
+        if level != LintLevel::Warn {
+            self.levels.push((lint, level));
+        }
+        self
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels
+            .iter()
+            .find(|(configured, _)| configured == &lint)
+            .map(|(_, level)| *level)
+            .unwrap_or(LintLevel::Warn)
+    }
+
+    pub fn is_enabled(&self, lint: Lint) -> bool {
+        self.level(lint) != LintLevel::Allow
+    }
+
+    // lintに該当する記述を報告する際の重要度 (Allowの場合は報告しない)
+    pub fn severity(&self, lint: Lint) -> Option<Severity> {
+        match self.level(lint) {
+            LintLevel::Allow => None,
+            LintLevel::Warn => Some(Severity::Warning),
+            LintLevel::Deny => Some(Severity::Error),
+        }
+    }
+}
+
+// 検査済みのSysDCSystemを対象に，エラーではないが疑わしい記述を警告として報告する
+// ※Denyが設定されたLintに該当する記述はエラーとして報告される
+// ※importsはsystem.unitsと同じ順序で並んだ，各Unitのimport一覧
+pub fn lint(
+    system: &SysDCSystem,
+    imports: &[Vec<SysDCImport>],
+    config: &LintConfig,
+) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        config,
+        kinds: collect_function_kinds(system),
+        warnings: vec![],
+    };
+    for (unit, imports) in system.units.iter().zip(imports.iter()) {
+        linter.lint_unit(unit, imports);
+    }
+    linter.warnings
+}
+
+// ※kindsは関数の完全な名前ごとの，宣言された種類(func, proc)
+struct Linter<'a> {
+    config: &'a LintConfig,
+    kinds: HashMap<String, FunctionKind>,
+    warnings: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    fn lint_unit(&mut self, unit: &SysDCUnit, imports: &[SysDCImport]) {
+        for data in &unit.data {
+            self.lint_data(data);
+        }
+        for module in &unit.modules {
+            self.lint_module(module);
+        }
+
+        // Unit内で参照されている定義の中に，importした定義(またはその子)が含まれているか
+        // ※import std.time; の場合は，Unit内の定義(またはその子)が含まれているか
+        // ※ワイルドカード(from std.io import *;)は対象外
+        let refs = collect_refs_in_unit(unit);
+        for import in imports {
+            let (alias, is_used) = match import {
+                SysDCImport::Item { target, alias } => {
+                    let import_name = target.get_full_name();
+                    let is_used = refs.iter().any(|refs| {
+                        refs == &import_name || refs.starts_with(&format!("{}.", import_name))
+                    });
+                    (alias, is_used)
+                }
+                SysDCImport::Unit { target, alias } => {
+                    let unit_name = format!("{}.", target.get_full_name());
+                    (alias, refs.iter().any(|refs| refs.starts_with(&unit_name)))
+                }
+                SysDCImport::Wildcard(_) => continue,
+            };
+            if !is_used {
+                self.warn(
+                    Lint::UnusedImport,
+                    PErrorKind::UnusedImport(alias.name.clone()),
+                    alias,
+                );
+            }
+        }
+    }
+
+    fn lint_data(&mut self, data: &SysDCData) {
+        if data.members.is_empty() && data.extends.is_empty() {
+            self.warn(
+                Lint::EmptyData,
+                PErrorKind::EmptyData(data.name.name.clone()),
+                &data.name,
+            );
+        }
+    }
+
+    fn lint_module(&mut self, module: &SysDCModule) {
+        if module.functions.is_empty() {
+            self.warn(
+                Lint::EmptyModule,
+                PErrorKind::EmptyModule(module.name.name.clone()),
+                &module.name,
+            );
+        }
+        for func in &module.functions {
+            self.lint_function(func);
+        }
+    }
+
+    fn lint_function(&mut self, func: &SysDCFunction) {
+        // 本体を持たない関数は宣言のみとみなし，引数については報告しない
+        let uses = collect_uses_in_function(func);
+        if !func.annotations.is_empty() || func.returns.1.kind != TypeKind::Void {
+            for (arg, _) in &func.args {
+                if !uses.iter().any(|used| refers_to(used, arg)) {
+                    self.warn(
+                        Lint::UnusedArgument,
+                        PErrorKind::UnusedArgument(arg.name.clone()),
+                        arg,
+                    );
+                }
+            }
+        }
+
+        // funcは副作用を持たないため，affectの対象とすることは疑わしい
+        for annotation in &func.annotations {
+            if let SysDCAnnotation::Affect {
+                func: (called, _), ..
+            } = annotation
+            {
+                if self.kinds.get(&called.get_full_name()) == Some(&FunctionKind::Func) {
+                    self.warn(
+                        Lint::AffectFunc,
+                        PErrorKind::AffectOnFunc(called.name.clone()),
+                        called,
+                    );
+                }
+            }
+        }
+
+        for annotation in &func.annotations {
+            if let SysDCAnnotation::Spawn {
+                result: (result, _),
+                details,
+            } = annotation
+            {
+                let mut vars = vec![result];
+                for detail in details {
+                    if let SysDCSpawnDetail::LetTo { name, .. } = detail {
+                        vars.push(name);
+                    }
+                }
+                for var in vars {
+                    if !uses.iter().any(|used| refers_to(used, var)) {
+                        self.warn(
+                            Lint::UnusedVariable,
+                            PErrorKind::UnusedVariable(var.name.clone()),
+                            var,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn warn(&mut self, lint: Lint, kind: PErrorKind, name: &Name) {
+        if let Some(severity) = self.config.severity(lint) {
+            let warning = PError::from(kind).with_loc(name.loc.clone());
+            self.warnings.push(Diagnostic::new(severity, warning));
+        }
+    }
+}
+
+// Module, Interfaceに定義されたすべての関数の，完全な名前と宣言された種類の対応
+fn collect_function_kinds(system: &SysDCSystem) -> HashMap<String, FunctionKind> {
+    let mut kinds = HashMap::new();
+    for unit in &system.units {
+        let interface_funcs = unit.interfaces.iter().flat_map(|it| &it.functions);
+        let module_funcs = unit.modules.iter().flat_map(|module| &module.functions);
+        for func in interface_funcs.chain(module_funcs) {
+            kinds.insert(func.name.get_full_name(), func.kind);
+        }
+    }
+    kinds
+}
+
+// usedがvarを参照しているかどうか
+// ※メンバへのアクセス(var.member)や，spawn内(namespaceに _ が付与される)からの参照も含む
+fn refers_to(used: &Name, var: &Name) -> bool {
+    if used.name.split('.').next() != Some(var.name.as_str()) {
+        return false;
+    }
+    match used.namespace.strip_prefix(&var.namespace) {
+        Some(rest) => rest.split('.').all(|elem| elem.is_empty() || elem == "_"),
+        None => false,
+    }
+}
+
+// 関数内で値として参照されている名前の一覧
+fn collect_uses_in_function(func: &SysDCFunction) -> Vec<&Name> {
+    let mut uses = vec![&func.returns.0];
+    for annotation in &func.annotations {
+        match annotation {
+            SysDCAnnotation::Affect { args, .. } => {
+                uses.extend(args.iter().map(|(name, _)| name));
+            }
+            SysDCAnnotation::Modify {
+                target,
+                uses: _uses,
+            } => {
+                uses.push(&target.0);
+                uses.extend(_uses.iter().map(|(name, _)| name));
+            }
+            SysDCAnnotation::Spawn { details, .. } => {
+                for detail in details {
+                    match detail {
+                        SysDCSpawnDetail::Use(name, _) | SysDCSpawnDetail::Return(name, _) => {
+                            uses.push(name)
+                        }
+                        SysDCSpawnDetail::LetTo { args, .. } => {
+                            uses.extend(args.iter().map(|(name, _)| name));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    uses
+}
+
+// Unit内で参照されている定義(型, 関数, Interface)の完全な名前の一覧
+fn collect_refs_in_unit(unit: &SysDCUnit) -> Vec<String> {
+    let mut types: Vec<&Type> = vec![];
+    let mut funcs: Vec<&Name> = vec![];
+    for data in &unit.data {
+        types.extend(data.extends.iter());
+        types.extend(data.members.iter().map(|(_, types)| types));
+    }
+    for _enum in &unit.enums {
+        for variant in &_enum.variants {
+            types.extend(variant.members.iter().map(|(_, types)| types));
+        }
+    }
+    for interface in &unit.interfaces {
+        for func in &interface.functions {
+            types.extend(func.args.iter().map(|(_, types)| types));
+            types.push(&func.returns.1);
+        }
+    }
+    for module in &unit.modules {
+        funcs.extend(module.implements.iter());
+        for func in &module.functions {
+            types.extend(func.args.iter().map(|(_, types)| types));
+            types.push(&func.returns.1);
+            for annotation in &func.annotations {
+                match annotation {
+                    SysDCAnnotation::Affect {
+                        func,
+                        type_args,
+                        args,
+                    } => {
+                        funcs.push(&func.0);
+                        types.extend(type_args.iter());
+                        types.extend(args.iter().map(|(_, types)| types));
+                    }
+                    SysDCAnnotation::Modify { target, uses } => {
+                        types.push(&target.1);
+                        types.extend(uses.iter().map(|(_, types)| types));
+                    }
+                    SysDCAnnotation::Spawn { result, details } => {
+                        types.push(&result.1);
+                        for detail in details {
+                            match detail {
+                                SysDCSpawnDetail::Use(_, types_)
+                                | SysDCSpawnDetail::Return(_, types_) => types.push(types_),
+                                SysDCSpawnDetail::LetTo {
+                                    func,
+                                    type_args,
+                                    args,
+                                    ..
+                                } => {
+                                    funcs.push(&func.0);
+                                    types.push(&func.1);
+                                    types.extend(type_args.iter());
+                                    types.extend(args.iter().map(|(_, types)| types));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut refs = funcs
+        .into_iter()
+        .map(|func| func.get_full_name())
+        .collect::<Vec<String>>();
+    // ※型引数(List<T>のTなど)で参照されている型も辿る
+    while let Some(_type) = types.pop() {
+        if let Some(name) = &_type.refs {
+            refs.push(name.get_full_name());
+        }
+        types.extend(_type.params.iter());
+    }
+    refs
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Lint, LintConfig, LintLevel};
+    use crate::error::{PError, PErrorKind};
+    use crate::Parser;
+
+    #[test]
+    fn unused_argument() {
+        let program = "
+            unit test;
+
+            data A {
+                x: i32
+            }
+
+            module TestModule {
+                proc test(a: A, b: A, c: A) {
+                    @modify a {
+                        use c;
+                    }
+                }
+
+                proc declaration_only(a: A) {}
+            }
+        ";
+        assert_eq!(lint(vec![program], LintConfig::new()), vec!["W0001 b"]);
+    }
+
+    #[test]
+    fn unused_variable() {
+        let program = "
+            unit test;
+
+            data A {
+                x: i32
+            }
+
+            module TestModule {
+                func test(a: A) -> i32 {
+                    @return v
+
+                    @spawn v: i32 {
+                        use a;
+                        let v1 = get(a.x);
+                        let v2 = get(a.x);
+                        return v1;
+                    }
+
+                    @spawn w: i32
+                }
+
+                func get(x: i32) -> i32 {
+                    @return x
+                }
+            }
+        ";
+        assert_eq!(
+            lint(vec![program], LintConfig::new()),
+            vec!["W0002 v2", "W0002 w"]
+        );
+    }
+
+    #[test]
+    fn unused_import() {
+        let program1 = "
+            unit test;
+
+            from test2 import A, B, M;
+
+            module TestModule {
+                proc test(a: A) {
+                    @affect M.test(a)
+                }
+            }
+        ";
+        let program2 = "
+            unit test2;
+
+            pub data A {
+                x: i32
+            }
+
+            pub data B {
+                x: i32
+            }
+
+            pub module M {
+                pub proc test(a: A) {
+                    @modify a
+                }
+            }
+        ";
+        assert_eq!(
+            lint(vec![program1, program2], LintConfig::new()),
+            vec!["W0003 B"]
+        );
+    }
+
+    #[test]
+    fn unused_import_with_alias() {
+        let program1 = "
+            unit test;
+
+            from test2 import A as AliasA, B as AliasB;
+            import test2 as t;
+            import test3;
+
+            data Group {
+                a: AliasA,
+                c: t.C
+            }
+        ";
+        let program2 = "
+            unit test2;
+
+            pub data A {
+                x: i32
+            }
+
+            pub data B {
+                x: i32
+            }
+
+            pub data C {
+                x: i32
+            }
+        ";
+        let program3 = "
+            unit test3;
+        ";
+        assert_eq!(
+            lint(vec![program1, program2, program3], LintConfig::new()),
+            vec!["W0003 AliasB", "W0003 test3"]
+        );
+    }
+
+    #[test]
+    fn import_used_in_type_params() {
+        let program1 = "
+            unit test;
+
+            from test2 import A, B;
+
+            data Group {
+                members: Map<i32, List<A>>
+            }
+        ";
+        let program2 = "
+            unit test2;
+
+            pub data A {
+                x: i32
+            }
+
+            pub data B {
+                x: i32
+            }
+        ";
+        assert_eq!(
+            lint(vec![program1, program2], LintConfig::new()),
+            vec!["W0003 B"]
+        );
+    }
+
+    #[test]
+    fn import_used_in_implements() {
+        let program1 = "
+            unit test;
+
+            from test2 import Blob, Storage;
+
+            module FileStorage implements Storage {
+                pub proc save(x: Blob) {
+                    @modify x
+                }
+            }
+        ";
+        let program2 = "
+            unit test2;
+
+            pub data Blob {
+                x: i32
+            }
+
+            pub interface Storage {
+                proc save(x: Blob)
+            }
+        ";
+        assert!(lint(vec![program1, program2], LintConfig::new()).is_empty());
+    }
+
+    #[test]
+    fn empty_data_and_module() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            data B = A
+
+            module TestModule {}
+        ";
+        assert_eq!(
+            lint(vec![program], LintConfig::new()),
+            vec!["W0004 A", "W0005 TestModule"]
+        );
+    }
+
+    #[test]
+    fn disable_lint() {
+        let program = "
+            unit test;
+
+            data A {}
+
+            module TestModule {}
+        ";
+        let config = LintConfig::new().with_lint_level(Lint::EmptyData, LintLevel::Allow);
+        assert_eq!(
+            lint(vec![program], config.clone()),
+            vec!["W0005 TestModule"]
+        );
+
+        let config = config.with_lint_level(Lint::EmptyData, LintLevel::Warn);
+        assert_eq!(lint(vec![program], config).len(), 2);
+    }
+
+    #[test]
+    fn import_cycle() {
+        let program1 = "
+            unit test.a;
+
+            from test.b import B;
+
+            pub data A {
+                b: B
+            }
+        ";
+        let program2 = "
+            unit test.b;
+
+            import test.c;
+
+            pub data B {
+                inner: c.C
+            }
+        ";
+        let program3 = "
+            unit test.c;
+
+            from test.a import *;
+            from test.c import C;
+
+            pub data C {
+                a: Option<A>
+            }
+        ";
+        assert_eq!(
+            lint(vec![program1, program2, program3], LintConfig::new()),
+            vec!["W0006 test.a -> test.b -> test.c -> test.a", "W0003 C"]
+        );
+    }
+
+    #[test]
+    fn import_cycle_location() {
+        let program1 = "unit test.a;
+
+from test.b import B;
+
+pub data A {
+    b: B
+}
+";
+        let program2 = "unit test.b;
+
+// Aを参照する
+from test.a import A;
+
+pub data B {
+    a: Option<A>
+}
+";
+        let err = lint_errors(vec![program1, program2], LintConfig::new());
+        assert_eq!(format!("{}", err[0].location()), "lint.def:3:20");
+        let (label_loc, label_message) = &err[0].labels()[0];
+        assert_eq!(format!("{}", label_loc), "lint.def:4:20");
+        assert_eq!(label_message, "\"test.b\" imports \"test.a\" here");
+    }
+
+    #[test]
+    fn affect_func() {
+        let program = "
+            unit test;
+
+            data A {
+                x: i32
+            }
+
+            module TestModule {
+                func make() -> A {
+                    @return a
+                    @spawn a: A
+                }
+
+                proc save() {}
+
+                proc test() {
+                    @affect make()
+                    @affect save()
+                }
+            }
+        ";
+        assert_eq!(lint(vec![program], LintConfig::new()), vec!["W0007 make"]);
+        assert!(lint(
+            vec![program],
+            LintConfig::new().with_lint_level(Lint::AffectFunc, LintLevel::Allow)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn affect_func_returns_void() {
+        let program = "
+            unit test;
+
+            interface Logger {
+                func flush() -> void
+            }
+
+            module TestModule {
+                func log() -> void {
+                    @return done
+                    @spawn done: void
+                }
+
+                proc test() {
+                    @affect log()
+                    @affect Logger.flush()
+                }
+            }
+        ";
+        assert_eq!(
+            lint(vec![program], LintConfig::new()),
+            vec!["W0007 log", "W0007 flush"]
+        );
+    }
+
+    #[test]
+    fn deny_lint() {
+        let program = "
+            unit test;
+
+            data A {}
+        ";
+        let config = LintConfig::new().with_lint_level(Lint::EmptyData, LintLevel::Deny);
+        let mut parser = Parser::default().with_lint_config(config);
+        parser.parse("lint.def".to_string(), program).unwrap();
+        let (system, diagnostics) = parser.check_with_diagnostics();
+        assert!(system.is_none());
+        assert!(diagnostics[0].is_error());
+        assert_eq!(diagnostics[0].error.kind().code(), "W0004");
+
+        let program1 = "
+            unit test.a;
+
+            from test.b import *;
+        ";
+        let program2 = "
+            unit test.b;
+
+            from test.a import *;
+        ";
+        let config = LintConfig::new().with_lint_level(Lint::ImportCycle, LintLevel::Deny);
+        let err = lint_errors(vec![program1, program2], config.clone());
+        assert_eq!(err[0].kind().code(), "W0006");
+
+        let mut parser = Parser::default().with_lint_config(config);
+        parser.parse("lint.def".to_string(), program1).unwrap();
+        parser.parse("lint.def".to_string(), program2).unwrap();
+        assert!(parser.check().is_err());
+    }
+
+    #[test]
+    fn lint_name() {
+        for lint in Lint::all() {
+            assert_eq!(Lint::from_name(lint.name()), Some(lint));
+        }
+        assert_eq!(Lint::from_name("unknown"), None);
+    }
+
+    fn lint(programs: Vec<&str>, config: LintConfig) -> Vec<String> {
+        let mut parser = Parser::default().with_lint_config(config);
+        for program in programs {
+            parser.parse("lint.def".to_string(), program).unwrap();
+        }
+        let (system, diagnostics) = parser.check_with_diagnostics();
+        assert!(system.is_some());
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| {
+                let name = match diagnostic.error.kind() {
+                    PErrorKind::UnusedArgument(name)
+                    | PErrorKind::UnusedVariable(name)
+                    | PErrorKind::UnusedImport(name)
+                    | PErrorKind::EmptyData(name)
+                    | PErrorKind::EmptyModule(name)
+                    | PErrorKind::ImportCycle(name)
+                    | PErrorKind::AffectOnFunc(name) => name.clone(),
+                    _ => panic!(),
+                };
+                format!("{} {}", diagnostic.error.kind().code(), name)
+            })
+            .collect()
+    }
+
+    fn lint_errors(programs: Vec<&str>, config: LintConfig) -> Vec<PError> {
+        let mut parser = Parser::default().with_lint_config(config);
+        for program in programs {
+            parser.parse("lint.def".to_string(), program).unwrap();
+        }
+        let (_, diagnostics) = parser.check_with_diagnostics();
+        diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.error)
+            .collect()
+    }
+}
@@ -1,18 +1,23 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-// ソースコード上での位置を表す構造体
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+// ソースコード上での位置(範囲)を表す構造体
+// ※begin, endはともに(行, 列)で表され，endは範囲の直後を指す
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Location {
-    filename: Option<String>,
-    coord: Option<(i32, i32)>,
+    pub filename: Option<String>,
+    pub begin: Option<(i32, i32)>,
+    pub end: Option<(i32, i32)>,
 }
 
 impl Location {
     pub fn new() -> Location {
         Location {
             filename: None,
-            coord: None,
+            begin: None,
+            end: None,
         }
     }
 
@@ -22,14 +27,32 @@ impl Location {
     }
 
     pub fn with_coord(mut self, coord: (i32, i32)) -> Location {
-        self.coord = Some(coord);
+        self.begin = Some(coord);
+        self
+    }
+
+    pub fn with_end_coord(mut self, coord: (i32, i32)) -> Location {
+        self.end = Some(coord);
         self
     }
+
+    // selfの開始位置からendの終了位置までを範囲とするLocationを返す
+    pub fn until(&self, end: &Location) -> Location {
+        Location {
+            filename: self.filename.clone(),
+            begin: self.begin,
+            end: end.end.or(end.begin),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filename.is_none() && self.begin.is_none()
+    }
 }
 
 impl Display for Location {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match (&self.filename, self.coord) {
+        match (&self.filename, self.begin) {
             (Some(filename), Some((row, col))) => {
                 write!(f, "{}:{}:{}", filename, row, col)
             }
@@ -43,3 +66,24 @@ impl Display for Location {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Location;
+
+    #[test]
+    fn until() {
+        let begin = Location::new()
+            .with_filename("test.def".to_string())
+            .with_coord((1, 1))
+            .with_end_coord((1, 5));
+        let end = Location::new()
+            .with_filename("test.def".to_string())
+            .with_coord((3, 1))
+            .with_end_coord((3, 2));
+        let span = begin.until(&end);
+        assert_eq!(span.begin, Some((1, 1)));
+        assert_eq!(span.end, Some((3, 2)));
+        assert_eq!(format!("{}", span), "test.def:1:1");
+    }
+}
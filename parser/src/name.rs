@@ -2,10 +2,17 @@ use std::fmt::{Debug, Formatter};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+use super::location::Location;
+
+// ※locは名前が記述された位置を表すため，比較の対象には含めない
+// ※docは定義(Unit, Data, メンバ, Module, 関数, 引数など)に付与されたドキュメントコメント (比較の対象には含めない)
+#[derive(Clone, Eq, Serialize, Deserialize)]
 pub struct Name {
     pub name: String,
     pub namespace: String,
+    pub loc: Location,
+    #[serde(default)]
+    pub doc: Option<String>,
 }
 
 impl Name {
@@ -13,6 +20,8 @@ impl Name {
         Name {
             name,
             namespace: base.get_full_name(),
+            loc: Location::new(),
+            doc: None,
         }
     }
 
@@ -20,9 +29,21 @@ impl Name {
         Name {
             name: "0".to_string(),
             namespace: "".to_string(),
+            loc: Location::new(),
+            doc: None,
         }
     }
 
+    pub fn with_loc(mut self, loc: Location) -> Name {
+        self.loc = loc;
+        self
+    }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> Name {
+        self.doc = doc;
+        self
+    }
+
     pub fn get_full_name(&self) -> String {
         self.namespace.clone() + "." + &self.name
     }
@@ -38,6 +59,8 @@ impl Name {
         Name {
             name: new_name,
             namespace: new_namespace,
+            loc: Location::new(),
+            doc: None,
         }
     }
 
@@ -52,6 +75,8 @@ impl Name {
         Name {
             name: par_name.to_string(),
             namespace: par_namespace,
+            loc: Location::new(),
+            doc: None,
         }
     }
 
@@ -60,6 +85,12 @@ impl Name {
     }
 }
 
+impl PartialEq for Name {
+    fn eq(&self, other: &Name) -> bool {
+        self.name == other.name && self.namespace == other.namespace
+    }
+}
+
 impl Debug for Name {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         write!(f, "{}", self.get_full_name())
@@ -69,6 +100,7 @@ impl Debug for Name {
 #[cfg(test)]
 mod test {
     use super::Name;
+    use crate::location::Location;
 
     #[test]
     fn create_name() {
@@ -79,4 +111,13 @@ mod test {
         let name = Name::new_root();
         assert_eq!(name.get_full_name(), ".0".to_string());
     }
+
+    #[test]
+    fn compare_name_ignoring_location() {
+        let root = Name::new_root();
+        let name1 = Name::new(&root, "aaa".to_string());
+        let name2 =
+            Name::new(&root, "aaa".to_string()).with_loc(Location::new().with_coord((1, 1)));
+        assert_eq!(name1, name2);
+    }
 }
@@ -1,8 +1,9 @@
 use super::error::{PError, PErrorKind};
+use super::location::Location;
 use super::name::Name;
-use super::structure::unchecked;
-use super::token::{TokenKind, Tokenizer};
-use super::types::{Type, TypeKind};
+use super::structure::{unchecked, FunctionKind, Visibility};
+use super::token::{Token, TokenKind, Tokenizer};
+use super::types::unchecked::{Type, TypeKind};
 
 // 複数要素を一気にパースするためのマクロ
 // - 返り値: Vec<T>
@@ -29,176 +30,664 @@ macro_rules! parse_list {
     }};
 }
 
+// Unitのトップレベルに記述される文
+enum Sentence {
+    Import(Vec<unchecked::SysDCImport>),
+    Rule(Vec<unchecked::SysDCRule>),
+    Data(unchecked::SysDCData),
+    Enum(unchecked::SysDCEnum),
+    Interface(unchecked::SysDCInterface),
+    Module(unchecked::SysDCModule),
+}
+
 pub struct UnitParser<'a> {
     tokenizer: Tokenizer<'a>,
+    errors: Vec<anyhow::Error>,
 }
 
 impl<'a> UnitParser<'a> {
-    pub fn parse(tokenizer: Tokenizer<'a>) -> anyhow::Result<unchecked::SysDCUnit> {
-        let mut parser = UnitParser { tokenizer };
-        parser.parse_root(Name::new_root())
+    // エラーが見つかった場合でも文の区切りから解析を再開し，見つかったエラーをすべて返す
+    pub fn parse(tokenizer: Tokenizer<'a>) -> Result<unchecked::SysDCUnit, Vec<anyhow::Error>> {
+        let mut parser = UnitParser {
+            tokenizer,
+            errors: vec![],
+        };
+        match parser.parse_root(Name::new_root()) {
+            Ok(unit) if parser.errors.is_empty() => Ok(unit),
+            Ok(_) => Err(parser.errors),
+            Err(err) => {
+                parser.errors.push(err);
+                Err(parser.errors)
+            }
+        }
+    }
+
+    // エラー発生後，depthの深さにおける文の区切り(; または })までトークンを読み飛ばす
+    // ※depthの深さにおける } は外側の構文に属するため消費しない
+    fn recover(&mut self, depth: u32) {
+        loop {
+            let kind = match self.tokenizer.peek() {
+                Ok(Some(kind)) => kind,
+                Ok(None) => break,
+                Err(err) => {
+                    self.errors.push(err);
+                    continue;
+                }
+            };
+            if self.tokenizer.get_depth() == depth && kind == TokenKind::BracketEnd {
+                break;
+            }
+            let _ = self.tokenizer.skip();
+            if self.tokenizer.get_depth() == depth
+                && (kind == TokenKind::Semicolon || kind == TokenKind::BracketEnd)
+            {
+                break;
+            }
+        }
     }
 
     /**
      * <root> ::= { <sentence> }
-     * <sentence> ::= unit <id_chain>; { <import> | <data> | <module> }
+     * <sentence> ::= unit <id_chain>; { <import> | <rule> | <data> | <enum> | <interface> | <module> }
      */
     fn parse_root(&mut self, namespace: Name) -> anyhow::Result<unchecked::SysDCUnit> {
         // unit <id_chain> ;
-        self.tokenizer.request(TokenKind::Unit)?;
+        let begin_token = self.tokenizer.request(TokenKind::Unit)?;
+        let begin = begin_token.location;
         let namespace = match self.parse_id_chain(&namespace)? {
-            Some((found_name, _)) => Name::new(&namespace, found_name.name),
+            Some((found_name, _)) => Name::new(&namespace, found_name.name)
+                .with_loc(found_name.loc)
+                .with_doc(begin_token.doc),
             None => {
                 return Err(PError::from(PErrorKind::UnitNameNotSpecified)
                     .with_loc(self.tokenizer.get_now_ref_loc())
                     .into())
             }
         };
-        self.tokenizer.request(TokenKind::Semicolon)?;
+        let end = self.tokenizer.request(TokenKind::Semicolon)?.location;
 
-        // { <import> | <data> | <module> }
+        // { <import> | <rule> | <data> | <enum> | <interface> | <module> }
         let mut imports = vec![];
         let mut data = vec![];
+        let mut enums = vec![];
+        let mut interfaces = vec![];
         let mut modules = vec![];
+        let mut rules = vec![];
         while self.tokenizer.exists_next() {
-            match (
-                self.parse_import()?,
-                self.parse_data(&namespace)?,
-                self.parse_module(&namespace)?,
-            ) {
-                (None, None, None) => {
-                    return Err(PError::from(PErrorKind::DataOrModuleNotFound)
-                        .with_loc(self.tokenizer.get_now_ref_loc())
-                        .into())
-                }
-                (i, d, m) => {
-                    if let Some(i) = i {
-                        imports.extend(i);
-                    }
-                    if let Some(d) = d {
-                        data.push(d);
-                    }
-                    if let Some(m) = m {
-                        modules.push(m);
+            match self.parse_sentence(&namespace) {
+                Ok(Sentence::Import(i)) => imports.extend(i),
+                Ok(Sentence::Rule(r)) => rules.extend(r),
+                Ok(Sentence::Data(d)) => data.push(d),
+                Ok(Sentence::Enum(e)) => enums.push(e),
+                Ok(Sentence::Interface(it)) => interfaces.push(it),
+                Ok(Sentence::Module(m)) => modules.push(m),
+                Err(err) => {
+                    self.errors.push(err);
+                    self.recover(0);
+
+                    // トップレベルに現れた余分な } は読み捨てる
+                    if let Err(err) = self.tokenizer.expect(TokenKind::BracketEnd) {
+                        self.errors.push(err);
                     }
                 }
             }
         }
 
-        Ok(unchecked::SysDCUnit::new(namespace, data, modules, imports))
+        // ファイル末尾まで閉じられていないコメントは，ここで報告される
+        if let Err(err) = self.tokenizer.peek() {
+            self.errors.push(err);
+        }
+
+        Ok(unchecked::SysDCUnit::new(namespace, data, modules, imports)
+            .with_enums(enums)
+            .with_interfaces(interfaces)
+            .with_rules(rules)
+            .with_loc(begin.until(&end)))
     }
 
     /**
-     * <import> ::= from <id_chain> import <id_list, delimiter=','> ;
+     * <import> | <rule> | <visibility> ( <data> | <enum> | <interface> | <module> )
      */
-    fn parse_import(&mut self) -> anyhow::Result<Option<Vec<Name>>> {
+    fn parse_sentence(&mut self, namespace: &Name) -> anyhow::Result<Sentence> {
+        // ※<visibility>は続く1つの定義にのみ適用される (pubが記述されている場合，import, ruleは続かない)
+        let visibility = self.parse_visibility()?;
+        if visibility.1.is_none() {
+            if let Some(i) = self.parse_import(namespace)? {
+                return Ok(Sentence::Import(i));
+            }
+            if let Some(r) = self.parse_rule()? {
+                return Ok(Sentence::Rule(r));
+            }
+        }
+        if let Some(d) = self.parse_data(namespace, &visibility)? {
+            return Ok(Sentence::Data(d));
+        }
+        if let Some(e) = self.parse_enum(namespace, &visibility)? {
+            return Ok(Sentence::Enum(e));
+        }
+        if let Some(it) = self.parse_interface(namespace, &visibility)? {
+            return Ok(Sentence::Interface(it));
+        }
+        if let Some(m) = self.parse_module(namespace, &visibility)? {
+            return Ok(Sentence::Module(m));
+        }
+        Err(PError::from(PErrorKind::SentenceNotFound)
+            .with_loc(self.tokenizer.get_now_ref_loc())
+            .into())
+    }
+
+    /**
+     * <visibility> ::= [ pub [ \( <id_chain> \) ] ]
+     */
+    // ※pubが記述されている場合は，定義の開始位置とドキュメントコメントを得るためにpubのトークンも返す
+    fn parse_visibility(&mut self) -> anyhow::Result<(Visibility, Option<Token>)> {
+        // pub
+        let pub_token = match self.tokenizer.expect(TokenKind::Pub)? {
+            Some(token) => token,
+            None => return Ok((Visibility::Private, None)),
+        };
+
+        // [ \( <id_chain> \) ]
+        if self
+            .tokenizer
+            .expect(TokenKind::ParenthesisBegin)?
+            .is_none()
+        {
+            return Ok((Visibility::Public, Some(pub_token)));
+        }
+        let scope = match self.parse_id_chain(&Name::new_root())? {
+            Some((found_name, _)) => found_name.name,
+            None => {
+                return Err(
+                    PError::from(PErrorKind::RequestedTokenNotFound(TokenKind::Identifier))
+                        .with_loc(self.tokenizer.get_now_ref_loc())
+                        .into(),
+                )
+            }
+        };
+        self.tokenizer.request(TokenKind::ParenthesisEnd)?;
+
+        Ok((Visibility::Restricted(scope), Some(pub_token)))
+    }
+
+    /**
+     * <import> ::= from <id_chain> import ( * | <import_item_list, delimiter=,> ) ;
+     *            | import <id_chain> ( as <id> ) ;
+     */
+    fn parse_import(
+        &mut self,
+        namespace: &Name,
+    ) -> anyhow::Result<Option<Vec<unchecked::SysDCImport>>> {
+        // import <id_chain> ( as <id> ) ;
+        if self.tokenizer.expect(TokenKind::Import)?.is_some() {
+            let target = self.parse_import_namespace()?;
+            let default = target.name.rsplit('.').next().unwrap().to_string();
+            let alias = self.parse_alias(namespace, default, &target.loc)?;
+            self.tokenizer.request(TokenKind::Semicolon)?;
+            return Ok(Some(vec![unchecked::SysDCImport::new_unit(target, alias)]));
+        }
+
         // from
         if self.tokenizer.expect(TokenKind::From)?.is_none() {
             return Ok(None);
         }
 
         // <id_chain>
-        let from_namespace = match self.parse_id_chain(&Name::new_root())? {
-            Some((found_name, _)) => Name::new(&Name::new_root(), found_name.name),
-            None => {
-                return Err(PError::from(PErrorKind::FromNamespaceNotSpecified)
-                    .with_loc(self.tokenizer.get_now_ref_loc())
-                    .into())
-            }
-        };
+        let from_namespace = self.parse_import_namespace()?;
 
-        // import <id_list, delimiter=','> ;
+        // import ( * | <import_item_list, delimiter=,> ) ;
         self.tokenizer.request(TokenKind::Import)?;
-        let mut importes = vec![];
-        for import in parse_list!(
-            self.tokenizer.expect(TokenKind::Identifier),
-            TokenKind::Separater
-        ) {
-            importes.push(Name::new(&from_namespace, import.orig));
-        }
+        let importes = if self.tokenizer.expect(TokenKind::Asterisk)?.is_some() {
+            vec![unchecked::SysDCImport::new_wildcard(from_namespace)]
+        } else {
+            parse_list!(
+                self.parse_import_item((namespace, &from_namespace)),
+                TokenKind::Separater
+            )
+        };
         self.tokenizer.request(TokenKind::Semicolon)?;
 
         Ok(Some(importes))
     }
 
     /**
-     * <data> ::= data <id> \{ <id_type_mapping_list, delimiter=,> \}
+     * <import_item> ::= <id> ( as <id> )
      */
-    fn parse_data(&mut self, namespace: &Name) -> anyhow::Result<Option<unchecked::SysDCData>> {
-        // data
-        if self.tokenizer.expect(TokenKind::Data)?.is_none() {
+    fn parse_import_item(
+        &mut self,
+        (namespace, from_namespace): (&Name, &Name),
+    ) -> anyhow::Result<Option<unchecked::SysDCImport>> {
+        let item = match self.tokenizer.expect(TokenKind::Identifier)? {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        let alias = self.parse_alias(namespace, item.orig.clone(), &item.location)?;
+        let target = Name::new(from_namespace, item.orig).with_loc(item.location);
+        Ok(Some(unchecked::SysDCImport::new_item(target, alias)))
+    }
+
+    // importするUnitの名前 (<id_chain>)
+    fn parse_import_namespace(&mut self) -> anyhow::Result<Name> {
+        match self.parse_id_chain(&Name::new_root())? {
+            Some((found_name, _)) => Ok(found_name),
+            None => Err(PError::from(PErrorKind::FromNamespaceNotSpecified)
+                .with_loc(self.tokenizer.get_now_ref_loc())
+                .into()),
+        }
+    }
+
+    // ( as <id> )
+    // ※asが記述されなかった場合は，default(位置はloc)を別名とする
+    fn parse_alias(
+        &mut self,
+        namespace: &Name,
+        default: String,
+        loc: &Location,
+    ) -> anyhow::Result<Name> {
+        if self.tokenizer.expect(TokenKind::As)?.is_none() {
+            return Ok(Name::new(namespace, default).with_loc(loc.clone()));
+        }
+        let alias = self.tokenizer.request(TokenKind::Identifier)?;
+        Ok(Name::new(namespace, alias.orig).with_loc(alias.location))
+    }
+
+    /**
+     * <rule> ::= rule \{ <deny_list> \}
+     * <deny> ::= deny <unit_pattern> -> <unit_pattern> ;
+     */
+    fn parse_rule(&mut self) -> anyhow::Result<Option<Vec<unchecked::SysDCRule>>> {
+        // rule {
+        if self.tokenizer.expect(TokenKind::Rule)?.is_none() {
             return Ok(None);
         }
+        self.tokenizer.request(TokenKind::BracketBegin)?;
+
+        // <deny_list>
+        let mut rules = vec![];
+        while let Some(deny_token) = self.tokenizer.expect(TokenKind::Deny)? {
+            let source = self.parse_unit_pattern()?;
+            self.tokenizer.request(TokenKind::Allow)?;
+            let target = self.parse_unit_pattern()?;
+            let end = self.tokenizer.request(TokenKind::Semicolon)?;
+            rules.push(
+                unchecked::SysDCRule::new(source, target)
+                    .with_loc(deny_token.location.until(&end.location)),
+            );
+        }
+
+        // }
+        self.tokenizer.request(TokenKind::BracketEnd)?;
+
+        Ok(Some(rules))
+    }
+
+    /**
+     * <unit_pattern> ::= <id_list, delimiter=.> [ . * ]
+     */
+    fn parse_unit_pattern(&mut self) -> anyhow::Result<unchecked::SysDCUnitPattern> {
+        let mut name_elems = vec![self.tokenizer.request(TokenKind::Identifier)?];
+        let mut sub_units = false;
+        while self.tokenizer.expect(TokenKind::Accessor)?.is_some() {
+            if self.tokenizer.expect(TokenKind::Asterisk)?.is_some() {
+                sub_units = true;
+                break;
+            }
+            name_elems.push(self.tokenizer.request(TokenKind::Identifier)?);
+        }
+        let loc = name_elems[0]
+            .location
+            .until(&name_elems[name_elems.len() - 1].location);
+        let name = name_elems
+            .into_iter()
+            .map(|x| x.orig)
+            .collect::<Vec<String>>()
+            .join(".");
+        Ok(unchecked::SysDCUnitPattern::new(
+            Name::new(&Name::new_root(), name).with_loc(loc),
+            sub_units,
+        ))
+    }
+
+    /**
+     * <data> ::= data <id> <type_params> ( <data_body> | = <data_compose_list, delimiter=+> )
+     * <data_compose> ::= <type> | <data_body>
+     */
+    fn parse_data(
+        &mut self,
+        namespace: &Name,
+        (visibility, pub_token): &(Visibility, Option<Token>),
+    ) -> anyhow::Result<Option<unchecked::SysDCData>> {
+        // data
+        let (begin, doc) = match self.tokenizer.expect(TokenKind::Data)? {
+            Some(token) => get_begin_and_doc(token, pub_token),
+            None => return Ok(None),
+        };
 
         // <id>
-        let name = Name::new(
-            namespace,
-            self.tokenizer.request(TokenKind::Identifier)?.orig,
-        );
+        let name_token = self.tokenizer.request(TokenKind::Identifier)?;
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location)
+            .with_doc(doc);
+
+        // <type_params>
+        let params = self.parse_type_params(&name)?;
+
+        // <data_body>
+        if self.tokenizer.expect(TokenKind::Equal)?.is_none() {
+            let (member, end) = self.parse_data_body(&name)?;
+            return Ok(Some(
+                unchecked::SysDCData::new(name, member)
+                    .with_visibility(visibility.clone())
+                    .with_params(params)
+                    .with_loc(begin.until(&end)),
+            ));
+        }
+
+        // = <data_compose_list, delimiter=+>
+        let mut extends = vec![];
+        let mut member = vec![];
+        let end = loop {
+            let end = if self.tokenizer.peek()? == Some(TokenKind::BracketBegin) {
+                let (_member, end) = self.parse_data_body(&name)?;
+                member.extend(_member);
+                end
+            } else {
+                let (types, loc) = self.parse_type()?;
+                // ※合成元の名前は記述された識別子 (Page<Log> の Page) とする
+                let base = match &types.kind {
+                    TypeKind::Unsolved(hint) => hint.clone(),
+                    _ => types.to_string(),
+                };
+                let base_name = Name::new(&name, base).with_loc(loc.clone());
+                extends.push((base_name, types));
+                loc
+            };
+            if self.tokenizer.expect(TokenKind::Plus)?.is_none() {
+                break end;
+            }
+        };
+
+        Ok(Some(
+            unchecked::SysDCData::new(name, member)
+                .with_visibility(visibility.clone())
+                .with_params(params)
+                .with_extends(extends)
+                .with_loc(begin.until(&end)),
+        ))
+    }
 
-        // \{ <id_type_mapping_list, delimiter=,> \}
+    /**
+     * <data_body> ::= \{ <id_type_mapping_list, delimiter=,> \}
+     */
+    fn parse_data_body(
+        &mut self,
+        namespace: &Name,
+    ) -> anyhow::Result<(Vec<(Name, Type)>, Location)> {
         self.tokenizer.request(TokenKind::BracketBegin)?;
-        let member = parse_list!(self.parse_id_type_mapping(&name), TokenKind::Separater);
-        self.tokenizer.request(TokenKind::BracketEnd)?;
+        let member = parse_list!(self.parse_id_type_mapping(namespace), TokenKind::Separater);
+        let end = self.tokenizer.request(TokenKind::BracketEnd)?.location;
+        Ok((member, end))
+    }
 
-        Ok(Some(unchecked::SysDCData::new(name, member)))
+    /**
+     * <enum> ::= enum <id> \{ <variant_list, delimiter=,> \}
+     */
+    fn parse_enum(
+        &mut self,
+        namespace: &Name,
+        (visibility, pub_token): &(Visibility, Option<Token>),
+    ) -> anyhow::Result<Option<unchecked::SysDCEnum>> {
+        // enum
+        let (begin, doc) = match self.tokenizer.expect(TokenKind::Enum)? {
+            Some(token) => get_begin_and_doc(token, pub_token),
+            None => return Ok(None),
+        };
+
+        // <id>
+        let name_token = self.tokenizer.request(TokenKind::Identifier)?;
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location)
+            .with_doc(doc);
+
+        // \{ <variant_list, delimiter=,> \}
+        self.tokenizer.request(TokenKind::BracketBegin)?;
+        let variants = parse_list!(self.parse_variant(&name), TokenKind::Separater);
+        let end = self.tokenizer.request(TokenKind::BracketEnd)?.location;
+
+        Ok(Some(
+            unchecked::SysDCEnum::new(name, variants)
+                .with_visibility(visibility.clone())
+                .with_loc(begin.until(&end)),
+        ))
     }
 
     /**
-     * <module> ::= module <id> \{ <function_list, delimiter=None> \}
+     * <variant> ::= <id> [ \( <id_type_mapping_list, delimiter=,> \) ]
      */
-    fn parse_module(&mut self, namespace: &Name) -> anyhow::Result<Option<unchecked::SysDCModule>> {
-        // module
-        if self.tokenizer.expect(TokenKind::Module)?.is_none() {
-            return Ok(None);
+    fn parse_variant(
+        &mut self,
+        namespace: &Name,
+    ) -> anyhow::Result<Option<unchecked::SysDCVariant>> {
+        // <id>
+        let name_token = match self.tokenizer.expect(TokenKind::Identifier)? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location.clone())
+            .with_doc(name_token.doc);
+
+        // [ \( <id_type_mapping_list, delimiter=,> \) ]
+        let (members, end) = match self.tokenizer.expect(TokenKind::ParenthesisBegin)? {
+            Some(_) => {
+                let members = parse_list!(self.parse_id_type_mapping(&name), TokenKind::Separater);
+                let end = self.tokenizer.request(TokenKind::ParenthesisEnd)?.location;
+                (members, end)
+            }
+            None => (vec![], name_token.location.clone()),
+        };
+
+        Ok(Some(
+            unchecked::SysDCVariant::new(name, members).with_loc(name_token.location.until(&end)),
+        ))
+    }
+
+    /**
+     * <interface> ::= interface <id> \{ <signature_list, delimiter=None> \}
+     */
+    fn parse_interface(
+        &mut self,
+        namespace: &Name,
+        (visibility, pub_token): &(Visibility, Option<Token>),
+    ) -> anyhow::Result<Option<unchecked::SysDCInterface>> {
+        // interface
+        let (begin, doc) = match self.tokenizer.expect(TokenKind::Interface)? {
+            Some(token) => get_begin_and_doc(token, pub_token),
+            None => return Ok(None),
+        };
+
+        // <id>
+        let name_token = self.tokenizer.request(TokenKind::Identifier)?;
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location)
+            .with_doc(doc);
+
+        // \{ <signature_list, delimiter=None> \}
+        self.tokenizer.request(TokenKind::BracketBegin)?;
+        let mut functions = vec![];
+        loop {
+            let depth = self.tokenizer.get_depth();
+            match self.parse_signature(&name) {
+                Ok(Some(function)) => functions.push(function),
+                Ok(None) => break,
+                Err(err) => {
+                    self.errors.push(err);
+                    self.recover(depth);
+                }
+            }
         }
+        let end = self.tokenizer.request(TokenKind::BracketEnd)?.location;
+
+        Ok(Some(
+            unchecked::SysDCInterface::new(name, functions)
+                .with_visibility(visibility.clone())
+                .with_loc(begin.until(&end)),
+        ))
+    }
+
+    /**
+     * <signature> ::= func <id> <id_type_mapping_list, delimiter=,> -> <type>
+     *               | proc <id> <id_type_mapping_list, delimiter=,>
+     */
+    // ※インターフェースの関数は，常に公開される
+    fn parse_signature(
+        &mut self,
+        namespace: &Name,
+    ) -> anyhow::Result<Option<unchecked::SysDCFunction>> {
+        // func | proc
+        let (is_func, begin, doc) = if let Some(token) = self.tokenizer.expect(TokenKind::Func)? {
+            (true, token.location, token.doc)
+        } else if let Some(token) = self.tokenizer.expect(TokenKind::Proc)? {
+            (false, token.location, token.doc)
+        } else {
+            return Ok(None);
+        };
 
         // <id>
-        let name = Name::new(
-            namespace,
-            self.tokenizer.request(TokenKind::Identifier)?.orig,
-        );
+        let name_token = self.tokenizer.request(TokenKind::Identifier)?;
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location)
+            .with_doc(doc);
+
+        // <id_type_mapping_list, delimiter=,>
+        self.tokenizer.request(TokenKind::ParenthesisBegin)?;
+        let args = parse_list!(self.parse_id_type_mapping(&name), TokenKind::Separater);
+        let mut end = self.tokenizer.request(TokenKind::ParenthesisEnd)?.location;
+
+        // ( -> <type> )
+        // ※本体を持たないため，返り値の名前には関数名そのもの(位置は型の位置)を用いる
+        let returns = if is_func {
+            self.tokenizer.request(TokenKind::Allow)?;
+            let (returns_type, returns_loc) = self.parse_type()?;
+            end = returns_loc.clone();
+            (
+                name.clone().with_loc(returns_loc).with_doc(None),
+                returns_type,
+            )
+        } else {
+            (Name::new_root(), Type::new(TypeKind::Void))
+        };
+
+        Ok(Some(
+            unchecked::SysDCFunction::new(name, args, returns, vec![])
+                .with_visibility(Visibility::Public)
+                .with_kind(function_kind(is_func))
+                .with_loc(begin.until(&end)),
+        ))
+    }
+
+    /**
+     * <module> ::= module <id> <type_params> ( implements <id_list, delimiter=,> ) \{ <function_list, delimiter=None> \}
+     */
+    fn parse_module(
+        &mut self,
+        namespace: &Name,
+        (visibility, pub_token): &(Visibility, Option<Token>),
+    ) -> anyhow::Result<Option<unchecked::SysDCModule>> {
+        // module
+        let (begin, doc) = match self.tokenizer.expect(TokenKind::Module)? {
+            Some(token) => get_begin_and_doc(token, pub_token),
+            None => return Ok(None),
+        };
+
+        // <id>
+        let name_token = self.tokenizer.request(TokenKind::Identifier)?;
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location)
+            .with_doc(doc);
+
+        // <type_params>
+        let params = self.parse_type_params(&name)?;
+
+        // ( implements <id_list, delimiter=,> )
+        let mut implements = vec![];
+        if self.tokenizer.expect(TokenKind::Implements)?.is_some() {
+            for interface in parse_list!(
+                self.tokenizer.expect(TokenKind::Identifier),
+                TokenKind::Separater
+            ) {
+                implements.push(Name::new(namespace, interface.orig).with_loc(interface.location));
+            }
+        }
 
         // \{ <function_list, delimiter=None> \}
         self.tokenizer.request(TokenKind::BracketBegin)?;
-        let functions = parse_list!(self.parse_function(&name));
-        self.tokenizer.request(TokenKind::BracketEnd)?;
-
-        Ok(Some(unchecked::SysDCModule::new(name, functions)))
+        let mut functions = vec![];
+        loop {
+            let depth = self.tokenizer.get_depth();
+            match self.parse_function(&name) {
+                Ok(Some(function)) => functions.push(function),
+                Ok(None) => break,
+                Err(err) => {
+                    self.errors.push(err);
+                    self.recover(depth);
+                }
+            }
+        }
+        let end = self.tokenizer.request(TokenKind::BracketEnd)?.location;
+
+        Ok(Some(
+            unchecked::SysDCModule::new(name, functions)
+                .with_visibility(visibility.clone())
+                .with_params(params)
+                .with_implements(implements)
+                .with_loc(begin.until(&end)),
+        ))
     }
 
     /**
-     * <function> ::= func <id> <id_type_mapping_list, delimiter=,> -> <id> \{ <function_body> \}
-     * <procedure> ::= proc <id> <id_type_mapping_list, delimiter=,> \{ <procedure_body > \}
+     * <function> ::= <visibility> func <id> <id_type_mapping_list, delimiter=,> -> <type> \{ <function_body> \}
+     * <procedure> ::= <visibility> proc <id> <id_type_mapping_list, delimiter=,> \{ <procedure_body > \}
      */
     fn parse_function(
         &mut self,
         namespace: &Name,
     ) -> anyhow::Result<Option<unchecked::SysDCFunction>> {
+        // <visibility>
+        let (visibility, pub_token) = self.parse_visibility()?;
+
         // func | proc
-        let is_func = self.tokenizer.expect(TokenKind::Func)?.is_some();
-        if !is_func && self.tokenizer.expect(TokenKind::Proc)?.is_none() {
+        let (is_func, token) = if let Some(token) = self.tokenizer.expect(TokenKind::Func)? {
+            (true, token)
+        } else if let Some(token) = self.tokenizer.expect(TokenKind::Proc)? {
+            (false, token)
+        } else if pub_token.is_some() {
+            return Err(
+                PError::from(PErrorKind::RequestedTokenNotFound(TokenKind::Func))
+                    .with_loc(self.tokenizer.get_now_ref_loc())
+                    .into(),
+            );
+        } else {
             return Ok(None);
-        }
+        };
+        let (begin, doc) = get_begin_and_doc(token, &pub_token);
 
         // <id>
-        let name = Name::new(
-            namespace,
-            self.tokenizer.request(TokenKind::Identifier)?.orig,
-        );
+        let name_token = self.tokenizer.request(TokenKind::Identifier)?;
+        let name = Name::new(namespace, name_token.orig)
+            .with_loc(name_token.location)
+            .with_doc(doc);
 
         // <id_type_mapping_list, delimiter=,>
         self.tokenizer.request(TokenKind::ParenthesisBegin)?;
         let args = parse_list!(self.parse_id_type_mapping(&name), TokenKind::Separater);
         self.tokenizer.request(TokenKind::ParenthesisEnd)?;
 
-        // ( -> <id> )
+        // ( -> <type> )
         let mut returns_type = None;
         if is_func {
             self.tokenizer.request(TokenKind::Allow)?;
-            returns_type = Some(Type::from(
-                self.tokenizer.request(TokenKind::Identifier)?.orig,
-            ));
+            returns_type = Some(self.parse_type()?.0);
         }
 
         // \{ <function_body> | <procedure_body> \}
@@ -208,18 +697,18 @@ impl<'a> UnitParser<'a> {
             ((return_name, returns_type.unwrap()), annotations)
         } else {
             (
-                (Name::new_root(), Type::new(TypeKind::Void, None)),
+                (Name::new_root(), Type::new(TypeKind::Void)),
                 self.parse_procedure_body(&name)?,
             )
         };
-        self.tokenizer.request(TokenKind::BracketEnd)?;
-
-        Ok(Some(unchecked::SysDCFunction::new(
-            name,
-            args,
-            returns,
-            annotations,
-        )))
+        let end = self.tokenizer.request(TokenKind::BracketEnd)?.location;
+
+        Ok(Some(
+            unchecked::SysDCFunction::new(name, args, returns, annotations)
+                .with_visibility(visibility)
+                .with_kind(function_kind(is_func))
+                .with_loc(begin.until(&end)),
+        ))
     }
 
     /**
@@ -317,14 +806,14 @@ impl<'a> UnitParser<'a> {
         if self.tokenizer.expect(TokenKind::Return)?.is_none() {
             return Ok(None);
         }
-        let returns = self.tokenizer.request(TokenKind::Identifier)?.orig;
-        Ok(Some(unchecked::SysDCAnnotation::new_return(Name::new(
-            namespace, returns,
-        ))))
+        let returns = self.tokenizer.request(TokenKind::Identifier)?;
+        Ok(Some(unchecked::SysDCAnnotation::new_return(
+            Name::new(namespace, returns.orig).with_loc(returns.location),
+        )))
     }
 
     /**
-     * <annotation_affect> ::= affect <id_chain> \( <id_chain_list, delimiter=,> \)
+     * <annotation_affect> ::= affect <func_ref> \( <id_chain_list, delimiter=,> \)
      */
     fn parse_annotation_affect(
         &mut self,
@@ -335,9 +824,9 @@ impl<'a> UnitParser<'a> {
             return Ok(None);
         }
 
-        // <id_chain>
-        let func = match self.parse_id_chain(namespace)? {
-            Some((name, _)) => (name.clone(), Type::from(name.name)),
+        // <func_ref>
+        let func = match self.parse_func_ref(namespace)? {
+            Some(func) => func,
             None => {
                 return Err(PError::from(PErrorKind::FunctionNameNotFound)
                     .with_loc(self.tokenizer.get_now_ref_loc())
@@ -368,7 +857,7 @@ impl<'a> UnitParser<'a> {
         // <id>
         let name_token = self.tokenizer.request(TokenKind::Identifier)?;
         let modify_target = (
-            Name::new(namespace, name_token.orig),
+            Name::new(namespace, name_token.orig).with_loc(name_token.location),
             Type::new_unsovled_nohint(),
         );
 
@@ -380,7 +869,10 @@ impl<'a> UnitParser<'a> {
                     self.tokenizer.expect(TokenKind::Identifier),
                     TokenKind::Separater
                 ) {
-                    uses.push((Name::new(namespace, name.orig), Type::new_unsovled_nohint()));
+                    uses.push((
+                        Name::new(namespace, name.orig).with_loc(name.location),
+                        Type::new_unsovled_nohint(),
+                    ));
                 }
                 self.tokenizer.request(TokenKind::Semicolon)?;
             }
@@ -436,7 +928,7 @@ impl<'a> UnitParser<'a> {
 
     /**
      * <annotation_spawn_detail> ::= (
-     *      let <id> = <id_chain> \( <id_chain_list, delimiter=','> \) ; |
+     *      let <id> = <func_ref> \( <id_chain_list, delimiter=','> \) ; |
      *      use <id_list, delimiter=','> ; |
      *      return <id> ;
      * )
@@ -448,17 +940,15 @@ impl<'a> UnitParser<'a> {
         // let
         if self.tokenizer.expect(TokenKind::Let)?.is_some() {
             // <id>
-            let let_to = Name::new(
-                namespace,
-                self.tokenizer.request(TokenKind::Identifier)?.orig,
-            );
+            let let_to_token = self.tokenizer.request(TokenKind::Identifier)?;
+            let let_to = Name::new(namespace, let_to_token.orig).with_loc(let_to_token.location);
 
             // =
             self.tokenizer.request(TokenKind::Equal)?;
 
-            // <id_chain>
-            let func = match self.parse_id_chain(namespace)? {
-                Some((func, _)) => func,
+            // <func_ref>
+            let func = match self.parse_func_ref(namespace)? {
+                Some(func) => func,
                 None => {
                     return Err(PError::from(PErrorKind::FunctionNameNotFound)
                         .with_loc(self.tokenizer.get_now_ref_loc())
@@ -475,9 +965,7 @@ impl<'a> UnitParser<'a> {
             self.tokenizer.request(TokenKind::Semicolon)?;
 
             return Ok(Some(vec![unchecked::SysDCSpawnDetail::new_let_to(
-                let_to,
-                (func.clone(), Type::from(func.name)),
-                args,
+                let_to, func, args,
             )]));
         }
 
@@ -489,7 +977,7 @@ impl<'a> UnitParser<'a> {
                 TokenKind::Separater
             ) {
                 var_list.push(unchecked::SysDCSpawnDetail::new_use(
-                    Name::new(namespace, token.orig),
+                    Name::new(namespace, token.orig).with_loc(token.location),
                     Type::new_unsovled_nohint(),
                 ))
             }
@@ -526,51 +1014,169 @@ impl<'a> UnitParser<'a> {
             self.tokenizer.expect(TokenKind::Identifier),
             TokenKind::Accessor
         );
+        let loc = match (name_elems.first(), name_elems.last()) {
+            (Some(first), Some(last)) => first.location.until(&last.location),
+            _ => return Ok(None),
+        };
         let var = name_elems
             .into_iter()
             .map(|x| x.orig)
             .collect::<Vec<String>>()
             .join(".");
-        match var.len() {
-            0 => Ok(None),
-            _ => Ok(Some((
-                Name::new(namespace, var),
-                Type::new_unsovled_nohint(),
-            ))),
-        }
+        Ok(Some((
+            Name::new(namespace, var).with_loc(loc),
+            Type::new_unsovled_nohint(),
+        )))
     }
 
     /**
      * <id_type_mapping> ::= <id> : <type>
      */
     fn parse_id_type_mapping(&mut self, namespace: &Name) -> anyhow::Result<Option<(Name, Type)>> {
-        let id1 = if let Some(id1_token) = self.tokenizer.expect(TokenKind::Identifier)? {
-            id1_token.orig
-        } else {
-            return Ok(None);
+        let id1_token = match self.tokenizer.expect(TokenKind::Identifier)? {
+            Some(id1_token) => id1_token,
+            None => return Ok(None),
         };
         self.tokenizer.request(TokenKind::Mapping)?;
-        Ok(Some((Name::new(namespace, id1), self.parse_type()?)))
+        let (types, type_loc) = self.parse_type()?;
+        let name = Name::new(namespace, id1_token.orig)
+            .with_loc(id1_token.location.until(&type_loc))
+            .with_doc(id1_token.doc);
+        Ok(Some((name, types)))
     }
 
     /**
-     * <type> ::= <id>
+     * <func_ref> ::= <id> <type_args> { . <id> }
      */
-    fn parse_type(&mut self) -> anyhow::Result<Type> {
+    fn parse_func_ref(&mut self, namespace: &Name) -> anyhow::Result<Option<(Name, Type)>> {
         // <id>
-        let id = self.tokenizer.request(TokenKind::Identifier)?.orig;
-        Ok(Type::from(id))
+        let head = match self.tokenizer.expect(TokenKind::Identifier)? {
+            Some(head) => head,
+            None => return Ok(None),
+        };
+
+        // <type_args>
+        let params = match self.parse_type_args()? {
+            Some((params, _)) => params,
+            None => vec![],
+        };
+
+        // { . <id> }
+        let mut elems = vec![head.orig];
+        let mut end = head.location.clone();
+        while self.tokenizer.expect(TokenKind::Accessor)?.is_some() {
+            let elem = self.tokenizer.request(TokenKind::Identifier)?;
+            elems.push(elem.orig);
+            end = elem.location;
+        }
+
+        let func = elems.join(".");
+        Ok(Some((
+            Name::new(namespace, func.clone()).with_loc(head.location.until(&end)),
+            Type::from(func).with_params(params),
+        )))
+    }
+
+    /**
+     * <type> ::= <id_chain> <type_args>
+     */
+    // ※<id_chain>はimportしたUnitの名前で修飾された型 (time.Timestamp) を表す
+    fn parse_type(&mut self) -> anyhow::Result<(Type, Location)> {
+        // <id_chain>
+        let id = self.tokenizer.request(TokenKind::Identifier)?;
+        let (mut hint, mut loc) = (id.orig, id.location);
+        while self.tokenizer.expect(TokenKind::Accessor)?.is_some() {
+            let elem = self.tokenizer.request(TokenKind::Identifier)?;
+            hint = format!("{}.{}", hint, elem.orig);
+            loc = loc.until(&elem.location);
+        }
+        let types = Type::from(hint);
+
+        // <type_args>
+        match self.parse_type_args()? {
+            Some((params, end)) => Ok((types.with_params(params), loc.until(&end))),
+            None => Ok((types, loc)),
+        }
+    }
+
+    /**
+     * <type_args> ::= ( \< <type_list, delimiter=,> \> )
+     */
+    fn parse_type_args(&mut self) -> anyhow::Result<Option<(Vec<Type>, Location)>> {
+        if self
+            .tokenizer
+            .expect(TokenKind::AngleBracketBegin)?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let mut params = vec![];
+        loop {
+            let (param, _) = self.parse_type()?;
+            params.push(param);
+            if self.tokenizer.expect(TokenKind::Separater)?.is_none() {
+                break;
+            }
+        }
+        let end = self.tokenizer.request(TokenKind::AngleBracketEnd)?.location;
+        Ok(Some((params, end)))
+    }
+
+    /**
+     * <type_params> ::= ( \< <id_list, delimiter=,> \> )
+     */
+    fn parse_type_params(&mut self, namespace: &Name) -> anyhow::Result<Vec<Name>> {
+        if self
+            .tokenizer
+            .expect(TokenKind::AngleBracketBegin)?
+            .is_none()
+        {
+            return Ok(vec![]);
+        }
+        let mut params = vec![];
+        for param in parse_list!(
+            self.tokenizer.expect(TokenKind::Identifier),
+            TokenKind::Separater
+        ) {
+            params.push(Name::new(namespace, param.orig).with_loc(param.location));
+        }
+        self.tokenizer.request(TokenKind::AngleBracketEnd)?;
+        Ok(params)
+    }
+}
+
+// 定義の開始位置とドキュメントコメント
+// ※可視性(pub)が記述されている場合は，pubのトークンの位置とドキュメントコメントを用いる
+fn get_begin_and_doc(token: Token, pub_token: &Option<Token>) -> (Location, Option<String>) {
+    match pub_token {
+        Some(pub_token) => (
+            pub_token.location.clone(),
+            pub_token.doc.clone().or(token.doc),
+        ),
+        None => (token.location, token.doc),
+    }
+}
+
+// func, procのどちらで宣言されたか
+fn function_kind(is_func: bool) -> FunctionKind {
+    if is_func {
+        FunctionKind::Func
+    } else {
+        FunctionKind::Proc
     }
 }
 
 #[cfg(test)]
 mod test {
+    use super::super::location::Location;
     use super::super::name::Name;
     use super::super::structure::unchecked::{
-        SysDCAnnotation, SysDCData, SysDCFunction, SysDCModule, SysDCSpawnDetail, SysDCUnit,
+        SysDCAnnotation, SysDCData, SysDCEnum, SysDCFunction, SysDCImport, SysDCInterface,
+        SysDCModule, SysDCRule, SysDCSpawnDetail, SysDCUnit, SysDCUnitPattern, SysDCVariant,
     };
+    use super::super::structure::{FunctionKind, Visibility};
     use super::super::token::Tokenizer;
-    use super::super::types::{Type, TypeKind};
+    use super::super::types::unchecked::{Type, TypeKind};
     use super::UnitParser;
 
     #[test]
@@ -601,7 +1207,12 @@ mod test {
 
         compare_unit(
             program,
-            SysDCUnit::new(generate_name_for_test(), vec![], vec![], name_imports),
+            SysDCUnit::new(
+                generate_name_for_test(),
+                vec![],
+                vec![],
+                import_items(&generate_name_for_test(), name_imports),
+            ),
         );
     }
 
@@ -639,10 +1250,206 @@ mod test {
 
         compare_unit(
             program,
-            SysDCUnit::new(generate_name_for_test(), vec![], vec![], name_imports),
+            SysDCUnit::new(
+                generate_name_for_test(),
+                vec![],
+                vec![],
+                import_items(&generate_name_for_test(), name_imports),
+            ),
+        );
+    }
+
+    #[test]
+    fn import_alias() {
+        let program = "
+            unit test;
+
+            from outer import A as B, C;
+        ";
+
+        let name = generate_name_for_test();
+        let name_outer = Name::new(&Name::new_root(), "outer".to_string());
+        let imports = vec![
+            SysDCImport::new_item(
+                Name::new(&name_outer, "A".to_string()),
+                Name::new(&name, "B".to_string()),
+            ),
+            SysDCImport::new_item(
+                Name::new(&name_outer, "C".to_string()),
+                Name::new(&name, "C".to_string()),
+            ),
+        ];
+
+        compare_unit(program, SysDCUnit::new(name, vec![], vec![], imports));
+    }
+
+    #[test]
+    fn import_wildcard() {
+        let program = "
+            unit test;
+
+            from outer.in import *;
+        ";
+
+        let name_outer_in = Name::new(
+            &Name::new(&Name::new_root(), "outer".to_string()),
+            "in".to_string(),
+        );
+        let imports = vec![SysDCImport::new_wildcard(name_outer_in)];
+
+        compare_unit(
+            program,
+            SysDCUnit::new(generate_name_for_test(), vec![], vec![], imports),
         );
     }
 
+    #[test]
+    fn import_unit() {
+        let program = "
+            unit test;
+
+            import std.time;
+            import std.io as stdio;
+
+            data Log {
+                at: time.Timestamp
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_std = Name::new(&Name::new_root(), "std".to_string());
+        let imports = vec![
+            SysDCImport::new_unit(
+                Name::new(&name_std, "time".to_string()),
+                Name::new(&name, "time".to_string()),
+            ),
+            SysDCImport::new_unit(
+                Name::new(&name_std, "io".to_string()),
+                Name::new(&name, "stdio".to_string()),
+            ),
+        ];
+        let name_data = Name::new(&name, "Log".to_string());
+        let name_data_at = Name::new(&name_data, "at".to_string());
+        let data = SysDCData::new(
+            name_data,
+            vec![(name_data_at, Type::from("time.Timestamp".to_string()))],
+        );
+
+        compare_unit(program, SysDCUnit::new(name, vec![data], vec![], imports));
+    }
+
+    #[test]
+    fn import_alias_location() {
+        let program = "unit test;
+
+from outer import A as B;
+import std.time;
+";
+        let unit = parse(program);
+        let locs = unit
+            .imports
+            .iter()
+            .map(|import| match import {
+                SysDCImport::Item { alias, .. } | SysDCImport::Unit { alias, .. } => {
+                    format!("{}", alias.loc)
+                }
+                SysDCImport::Wildcard(target) => format!("{}", target.loc),
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(locs, vec!["test.def:3:24", "test.def:4:8"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_alias_without_name() {
+        let program = "
+            unit test;
+
+            from outer import A as;
+        ";
+        parse(program);
+    }
+
+    #[test]
+    #[should_panic]
+    fn import_wildcard_with_items() {
+        let program = "
+            unit test;
+
+            from outer import *, A;
+        ";
+        parse(program);
+    }
+
+    #[test]
+    fn rule_ok() {
+        let program = "
+            unit test;
+
+            rule {
+                deny domain.* -> infra.*;
+                deny app.ui -> app.db;
+            }
+
+            rule {}
+        ";
+
+        let name = Name::new_root();
+        let pattern = |unit: &str, sub_units: bool| {
+            SysDCUnitPattern::new(Name::new(&name, unit.to_string()), sub_units)
+        };
+        let rules = vec![
+            SysDCRule::new(pattern("domain", true), pattern("infra", true)),
+            SysDCRule::new(pattern("app.ui", false), pattern("app.db", false)),
+        ];
+
+        compare_unit(
+            program,
+            SysDCUnit::new(generate_name_for_test(), vec![], vec![], vec![]).with_rules(rules),
+        );
+    }
+
+    #[test]
+    fn rule_location() {
+        let program = "unit test;
+
+rule {
+    deny domain.* -> infra;
+}
+";
+        let unit = parse(program);
+        let rule = &unit.rules[0];
+        assert_eq!(format!("{}", rule.loc), "test.def:4:5");
+        assert_eq!(format!("{}", rule.source.name.loc), "test.def:4:10");
+        assert_eq!(format!("{}", rule.target.name.loc), "test.def:4:22");
+    }
+
+    #[test]
+    #[should_panic]
+    fn rule_without_target() {
+        let program = "
+            unit test;
+
+            rule {
+                deny domain.*;
+            }
+        ";
+        parse(program);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rule_with_visibility() {
+        let program = "
+            unit test;
+
+            pub rule {
+                deny domain -> infra;
+            }
+        ";
+        parse(program);
+    }
+
     #[test]
     fn data_empty_ok() {
         let program = "
@@ -664,48 +1471,175 @@ mod test {
 
         let name = generate_name_for_test();
 
-        let data = vec![
-            SysDCData::new(Name::new(&name, "A".to_string()), vec![]),
-            SysDCData::new(Name::new(&name, "B".to_string()), vec![]),
-            SysDCData::new(Name::new(&name, "C".to_string()), vec![]),
-            SysDCData::new(Name::new(&name, "D".to_string()), vec![]),
-            SysDCData::new(Name::new(&name, "E".to_string()), vec![]),
+        let data = vec![
+            SysDCData::new(Name::new(&name, "A".to_string()), vec![]),
+            SysDCData::new(Name::new(&name, "B".to_string()), vec![]),
+            SysDCData::new(Name::new(&name, "C".to_string()), vec![]),
+            SysDCData::new(Name::new(&name, "D".to_string()), vec![]),
+            SysDCData::new(Name::new(&name, "E".to_string()), vec![]),
+        ];
+        let unit = SysDCUnit::new(name, data, vec![], vec![]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    fn data_has_member_ok() {
+        let program = "
+            unit test;
+
+            data Box {
+                x: i32,
+                y: UserDefinedData,
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_box = Name::new(&name, "Box".to_string());
+
+        let member = vec![
+            (
+                Name::new(&name_box, "x".to_string()),
+                Type::from("i32".to_string()),
+            ),
+            (
+                Name::new(&name_box, "y".to_string()),
+                Type::from("UserDefinedData".to_string()),
+            ),
+        ];
+        let data = SysDCData::new(name_box, member);
+        let unit = SysDCUnit::new(name, vec![data], vec![], vec![]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    fn data_has_generic_member_ok() {
+        let program = "
+            unit test;
+
+            data Group {
+                users: List<User>,
+                scores: Map<i32, Option<List<f32>>>,
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_group = Name::new(&name, "Group".to_string());
+
+        let member = vec![
+            (
+                Name::new(&name_group, "users".to_string()),
+                Type::from("List".to_string()).with_params(vec![Type::from("User".to_string())]),
+            ),
+            (
+                Name::new(&name_group, "scores".to_string()),
+                Type::from("Map".to_string()).with_params(vec![
+                    Type::from("i32".to_string()),
+                    Type::from("Option".to_string())
+                        .with_params(vec![Type::from("List".to_string())
+                            .with_params(vec![Type::from("f32".to_string())])]),
+                ]),
+            ),
+        ];
+        let data = SysDCData::new(name_group, member);
+        let unit = SysDCUnit::new(name, vec![data], vec![], vec![]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    #[should_panic]
+    fn data_has_unclosed_generic_member() {
+        let program = "
+            unit test;
+
+            data Group {
+                users: List<User,
+            }
+        ";
+        parse(program);
+    }
+
+    #[test]
+    fn data_has_type_params_ok() {
+        let program = "
+            unit test;
+
+            data Pair<K, V> {
+                key: K,
+                values: List<V>,
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_pair = Name::new(&name, "Pair".to_string());
+
+        let params = vec![
+            Name::new(&name_pair, "K".to_string()),
+            Name::new(&name_pair, "V".to_string()),
         ];
-        let unit = SysDCUnit::new(name, data, vec![], vec![]);
+        let member = vec![
+            (
+                Name::new(&name_pair, "key".to_string()),
+                Type::from("K".to_string()),
+            ),
+            (
+                Name::new(&name_pair, "values".to_string()),
+                Type::from("List".to_string()).with_params(vec![Type::from("V".to_string())]),
+            ),
+        ];
+        let data = SysDCData::new(name_pair, member).with_params(params);
+        let unit = SysDCUnit::new(name, vec![data], vec![], vec![]);
 
         compare_unit(program, unit);
     }
 
     #[test]
-    fn data_has_member_ok() {
+    fn data_composed_ok() {
         let program = "
             unit test;
 
-            data Box {
-                x: i32,
-                y: UserDefinedData,
+            data AdminUser = User + Page<Log> + {
+                permissions: Permissions
             }
         ";
 
         let name = generate_name_for_test();
-        let name_box = Name::new(&name, "Box".to_string());
-
-        let member = vec![
-            (
-                Name::new(&name_box, "x".to_string()),
-                Type::from("i32".to_string()),
-            ),
+        let name_data = Name::new(&name, "AdminUser".to_string());
+        let name_base_user = Name::new(&name_data, "User".to_string());
+        let name_base_page = Name::new(&name_data, "Page".to_string());
+        let name_member = Name::new(&name_data, "permissions".to_string());
+
+        let data = SysDCData::new(
+            name_data,
+            vec![(name_member, Type::from("Permissions".to_string()))],
+        )
+        .with_extends(vec![
+            (name_base_user, Type::from("User".to_string())),
             (
-                Name::new(&name_box, "y".to_string()),
-                Type::from("UserDefinedData".to_string()),
+                name_base_page,
+                Type::from("Page".to_string()).with_params(vec![Type::from("Log".to_string())]),
             ),
-        ];
-        let data = SysDCData::new(name_box, member);
+        ]);
         let unit = SysDCUnit::new(name, vec![data], vec![], vec![]);
 
         compare_unit(program, unit);
     }
 
+    #[test]
+    #[should_panic]
+    fn data_composed_without_plus() {
+        let program = "
+            unit test;
+
+            data AdminUser = User {
+                permissions: Permissions
+            }
+        ";
+        parse(program);
+    }
+
     #[test]
     #[should_panic]
     fn data_has_illegal_member_def_1() {
@@ -747,6 +1681,58 @@ mod test {
         parse(program);
     }
 
+    #[test]
+    fn enum_has_variants_ok() {
+        let program = "
+            unit test;
+
+            enum Status {
+                Active,
+                Suspended(reason: Reason, days: i32),
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_status = Name::new(&name, "Status".to_string());
+        let name_active = Name::new(&name_status, "Active".to_string());
+        let name_suspended = Name::new(&name_status, "Suspended".to_string());
+
+        let variants = vec![
+            SysDCVariant::new(name_active, vec![]),
+            SysDCVariant::new(
+                name_suspended.clone(),
+                vec![
+                    (
+                        Name::new(&name_suspended, "reason".to_string()),
+                        Type::from("Reason".to_string()),
+                    ),
+                    (
+                        Name::new(&name_suspended, "days".to_string()),
+                        Type::from("i32".to_string()),
+                    ),
+                ],
+            ),
+        ];
+        let _enum = SysDCEnum::new(name_status, variants);
+        let unit = SysDCUnit::new(name, vec![], vec![], vec![]).with_enums(vec![_enum]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    #[should_panic]
+    fn enum_has_illegal_variant_def() {
+        let program = "
+            unit test;
+
+            enum Status {
+                Active
+                Suspended(reason: i32)
+            }
+        ";
+        parse(program);
+    }
+
     #[test]
     fn module_empty_ok() {
         let program = "
@@ -798,7 +1784,38 @@ mod test {
         let name_func_ret = Name::new(&name_func, "box".to_string());
 
         let func_returns = (name_func_ret, Type::from("Box".to_string()));
-        let func = SysDCFunction::new(name_func, vec![], func_returns, vec![]);
+        let func = SysDCFunction::new(name_func, vec![], func_returns, vec![])
+            .with_kind(FunctionKind::Func);
+        let module = SysDCModule::new(name_module, vec![func]);
+
+        let unit = SysDCUnit::new(name, vec![], vec![module], vec![]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    fn function_returns_generic_type() {
+        let program = "
+            unit test;
+
+            module BoxModule {
+                func all() -> List<Box> {
+                    @return boxes
+                }
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_module = Name::new(&name, "BoxModule".to_string());
+        let name_func = Name::new(&name_module, "all".to_string());
+        let name_func_ret = Name::new(&name_func, "boxes".to_string());
+
+        let func_returns = (
+            name_func_ret,
+            Type::from("List".to_string()).with_params(vec![Type::from("Box".to_string())]),
+        );
+        let func = SysDCFunction::new(name_func, vec![], func_returns, vec![])
+            .with_kind(FunctionKind::Func);
         let module = SysDCModule::new(name_module, vec![func]);
 
         let unit = SysDCUnit::new(name, vec![], vec![module], vec![]);
@@ -806,6 +1823,239 @@ mod test {
         compare_unit(program, unit);
     }
 
+    #[test]
+    fn module_has_type_params_ok() {
+        let program = "
+            unit test;
+
+            module Repository<T> {
+                func save(item: T) -> T {
+                    @return item
+                }
+            }
+
+            module UserModule {
+                proc register(user: User) {
+                    @affect Repository<User>.save(user)
+                }
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_repo = Name::new(&name, "Repository".to_string());
+        let name_save = Name::new(&name_repo, "save".to_string());
+        let name_save_arg = Name::new(&name_save, "item".to_string());
+        let name_save_ret = Name::new(&name_save, "item".to_string());
+        let name_user_module = Name::new(&name, "UserModule".to_string());
+        let name_register = Name::new(&name_user_module, "register".to_string());
+        let name_register_arg = Name::new(&name_register, "user".to_string());
+        let name_register_affect = Name::new(&name_register, "Repository.save".to_string());
+        let name_register_affect_arg = Name::new(&name_register, "user".to_string());
+
+        let save = SysDCFunction::new(
+            name_save,
+            vec![(name_save_arg, Type::from("T".to_string()))],
+            (name_save_ret, Type::from("T".to_string())),
+            vec![],
+        )
+        .with_kind(FunctionKind::Func);
+        let repo = SysDCModule::new(name_repo.clone(), vec![save])
+            .with_params(vec![Name::new(&name_repo, "T".to_string())]);
+
+        let register = SysDCFunction::new(
+            name_register,
+            vec![(name_register_arg, Type::from("User".to_string()))],
+            (Name::new_root(), Type::new(TypeKind::Void)),
+            vec![SysDCAnnotation::new_affect(
+                (
+                    name_register_affect,
+                    Type::from("Repository.save".to_string())
+                        .with_params(vec![Type::from("User".to_string())]),
+                ),
+                vec![(name_register_affect_arg, Type::new_unsovled_nohint())],
+            )],
+        );
+        let user_module = SysDCModule::new(name_user_module, vec![register]);
+
+        let unit = SysDCUnit::new(name, vec![], vec![repo, user_module], vec![]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    fn interface_and_implements_ok() {
+        let program = "
+            unit test;
+
+            interface Storage {
+                proc save(x: Blob)
+                func load(id: u32) -> Blob
+            }
+
+            module FileStorage implements Storage {
+                proc save(x: Blob) {}
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_storage = Name::new(&name, "Storage".to_string());
+        let name_save = Name::new(&name_storage, "save".to_string());
+        let name_save_arg = Name::new(&name_save, "x".to_string());
+        let name_load = Name::new(&name_storage, "load".to_string());
+        let name_load_arg = Name::new(&name_load, "id".to_string());
+        let name_file_storage = Name::new(&name, "FileStorage".to_string());
+        let name_file_save = Name::new(&name_file_storage, "save".to_string());
+        let name_file_save_arg = Name::new(&name_file_save, "x".to_string());
+
+        let save = SysDCFunction::new(
+            name_save,
+            vec![(name_save_arg, Type::from("Blob".to_string()))],
+            (Name::new_root(), Type::new(TypeKind::Void)),
+            vec![],
+        )
+        .with_visibility(Visibility::Public);
+        let load = SysDCFunction::new(
+            name_load.clone(),
+            vec![(name_load_arg, Type::from("u32".to_string()))],
+            (name_load, Type::from("Blob".to_string())),
+            vec![],
+        )
+        .with_kind(FunctionKind::Func)
+        .with_visibility(Visibility::Public);
+        let storage = SysDCInterface::new(name_storage, vec![save, load]);
+
+        let file_save = SysDCFunction::new(
+            name_file_save,
+            vec![(name_file_save_arg, Type::from("Blob".to_string()))],
+            (Name::new_root(), Type::new(TypeKind::Void)),
+            vec![],
+        );
+        let file_storage = SysDCModule::new(name_file_storage, vec![file_save])
+            .with_implements(vec![Name::new(&name, "Storage".to_string())]);
+
+        let unit =
+            SysDCUnit::new(name, vec![], vec![file_storage], vec![]).with_interfaces(vec![storage]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    fn visibility_ok() {
+        let program = "
+            unit test;
+
+            pub data A {}
+
+            pub(app.core) enum B {}
+
+            interface C {}
+
+            pub module D {
+                pub func f() -> i32 {
+                    @return r
+                    @spawn r: i32
+                }
+
+                proc g() {}
+            }
+        ";
+
+        let name = generate_name_for_test();
+        let name_d = Name::new(&name, "D".to_string());
+        let name_f = Name::new(&name_d, "f".to_string());
+        let name_g = Name::new(&name_d, "g".to_string());
+        let name_r = Name::new(&name_f, "r".to_string());
+
+        let a = SysDCData::new(Name::new(&name, "A".to_string()), vec![])
+            .with_visibility(Visibility::Public);
+        let b = SysDCEnum::new(Name::new(&name, "B".to_string()), vec![])
+            .with_visibility(Visibility::Restricted("app.core".to_string()));
+        let c = SysDCInterface::new(Name::new(&name, "C".to_string()), vec![]);
+        let f = SysDCFunction::new(
+            name_f,
+            vec![],
+            (name_r.clone(), Type::from("i32".to_string())),
+            vec![SysDCAnnotation::new_spawn(
+                (name_r, Type::from("i32".to_string())),
+                vec![],
+            )],
+        )
+        .with_kind(FunctionKind::Func)
+        .with_visibility(Visibility::Public);
+        let g = SysDCFunction::new(
+            name_g,
+            vec![],
+            (Name::new_root(), Type::new(TypeKind::Void)),
+            vec![],
+        );
+        let d = SysDCModule::new(name_d, vec![f, g]).with_visibility(Visibility::Public);
+
+        let unit = SysDCUnit::new(name, vec![a], vec![d], vec![])
+            .with_enums(vec![b])
+            .with_interfaces(vec![c]);
+
+        compare_unit(program, unit);
+    }
+
+    #[test]
+    fn visibility_location_and_doc() {
+        let program = "unit test;
+
+%% 公開されたData %%
+pub data A {}";
+        let unit = parse(program);
+        assert_eq!(loc_to_tuple(&unit.data[0].loc), ((4, 1), (4, 14)));
+        assert_eq!(unit.data[0].name.doc, Some("公開されたData".to_string()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn visibility_without_definition() {
+        let program = "
+            unit test;
+
+            module A {
+                pub
+            }
+        ";
+        parse(program);
+    }
+
+    #[test]
+    #[should_panic]
+    fn visibility_on_import() {
+        let program = "
+            unit test;
+
+            pub from test2 import A;
+        ";
+        parse(program);
+    }
+
+    #[test]
+    #[should_panic]
+    fn visibility_without_scope() {
+        let program = "
+            unit test;
+
+            pub() data A {}
+        ";
+        parse(program);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interface_has_function_body() {
+        let program = "
+            unit test;
+
+            interface Storage {
+                proc save(x: Blob) {}
+            }
+        ";
+        parse(program);
+    }
+
     #[test]
     fn function_has_return_and_spawn() {
         let program = "
@@ -831,7 +2081,8 @@ mod test {
             vec![],
         )];
         let func_returns = (name_func_ret, Type::from("Box".to_string()));
-        let func = SysDCFunction::new(name_func, vec![], func_returns, func_annotations);
+        let func = SysDCFunction::new(name_func, vec![], func_returns, func_annotations)
+            .with_kind(FunctionKind::Func);
         let module = SysDCModule::new(name_module, vec![func]);
 
         let unit = SysDCUnit::new(name, vec![], vec![module], vec![]);
@@ -919,7 +2170,8 @@ mod test {
             ),
         ];
         let func_returns = (name_func_ret, Type::from("Box".to_string()));
-        let func = SysDCFunction::new(name_func, func_args, func_returns, func_annotations);
+        let func = SysDCFunction::new(name_func, func_args, func_returns, func_annotations)
+            .with_kind(FunctionKind::Func);
         let module = SysDCModule::new(name_module, vec![func]);
 
         let unit = SysDCUnit::new(name, vec![], vec![module], vec![]);
@@ -1002,7 +2254,7 @@ mod test {
         let name_module = Name::new(&name, "BoxModule".to_string());
         let name_proc = Name::new(&name_module, "new".to_string());
 
-        let proc_returns = (Name::new_root(), Type::new(TypeKind::Void, None));
+        let proc_returns = (Name::new_root(), Type::new(TypeKind::Void));
         let proc = SysDCFunction::new(name_proc, vec![], proc_returns, vec![]);
         let module = SysDCModule::new(name_module, vec![proc]);
 
@@ -1032,7 +2284,7 @@ mod test {
             (name_proc_spawn_box, Type::from("Box".to_string())),
             vec![],
         )];
-        let proc_returns = (Name::new_root(), Type::new(TypeKind::Void, None));
+        let proc_returns = (Name::new_root(), Type::new(TypeKind::Void));
         let proc = SysDCFunction::new(name_proc, vec![], proc_returns, proc_annotations);
         let module = SysDCModule::new(name_module, vec![proc]);
 
@@ -1191,7 +2443,8 @@ mod test {
             ),
         ];
         let func_returns = (name_func_ret, Type::from("Box".to_string()));
-        let func = SysDCFunction::new(name_func, func_args, func_returns, func_annotations);
+        let func = SysDCFunction::new(name_func, func_args, func_returns, func_annotations)
+            .with_kind(FunctionKind::Func);
         let module = SysDCModule::new(name_module, vec![func]);
 
         let data_members = vec![
@@ -1200,17 +2453,181 @@ mod test {
         ];
         let data = SysDCData::new(name_data, data_members);
 
-        let unit = SysDCUnit::new(name, vec![data], vec![module], name_imports);
+        let imports = import_items(&name, name_imports);
+        let unit = SysDCUnit::new(name, vec![data], vec![module], imports);
 
         compare_unit(program, unit);
     }
 
+    #[test]
+    fn location() {
+        let program = "unit test;
+
+data Box {
+    x: i32
+}
+
+module BoxModule {
+    func new(x: i32) -> Box {
+        @return box
+        @spawn box: Box {
+            use x;
+        }
+    }
+}";
+        let unit = parse(program);
+        assert_eq!(loc_to_tuple(&unit.loc), ((1, 1), (1, 11)));
+        assert_eq!(loc_to_tuple(&unit.name.loc), ((1, 6), (1, 10)));
+
+        let data = &unit.data[0];
+        assert_eq!(loc_to_tuple(&data.loc), ((3, 1), (5, 2)));
+        assert_eq!(loc_to_tuple(&data.name.loc), ((3, 6), (3, 9)));
+        assert_eq!(loc_to_tuple(&data.members[0].0.loc), ((4, 5), (4, 11)));
+
+        let module = &unit.modules[0];
+        assert_eq!(loc_to_tuple(&module.loc), ((7, 1), (14, 2)));
+
+        let func = &module.functions[0];
+        assert_eq!(loc_to_tuple(&func.loc), ((8, 5), (13, 6)));
+        assert_eq!(loc_to_tuple(&func.name.loc), ((8, 10), (8, 13)));
+        assert_eq!(loc_to_tuple(&func.args[0].0.loc), ((8, 14), (8, 20)));
+        assert_eq!(loc_to_tuple(&func.returns.0.loc), ((9, 17), (9, 20)));
+        match &func.annotations[0] {
+            SysDCAnnotation::Spawn { result, details } => {
+                assert_eq!(loc_to_tuple(&result.0.loc), ((10, 16), (10, 24)));
+                match &details[0] {
+                    SysDCSpawnDetail::Use(name, _) => {
+                        assert_eq!(loc_to_tuple(&name.loc), ((11, 17), (11, 18)))
+                    }
+                    _ => panic!(),
+                }
+            }
+            _ => panic!(),
+        }
+        assert_eq!(data.loc.filename, Some("test.def".to_string()));
+    }
+
+    #[test]
+    fn collect_multiple_errors() {
+        let program = "
+unit test;
+
+data A {
+    x: i32,
+    y
+}
+
+data B {
+    x: i32
+}
+
+data C {
+    x: i32 y: i32
+}
+";
+        let errors = parse_errors(program);
+        assert_eq!(errors.len(), 2);
+        assert!(format!("{}", errors[0]).ends_with("(at test.def:7:1)"));
+        assert!(format!("{}", errors[1]).ends_with("(at test.def:14:12)"));
+    }
+
+    #[test]
+    fn recover_in_module() {
+        let program = "
+unit test;
+
+module A {
+    func f() -> i32 {
+        @return
+    }
+
+    func g() -> i32 {
+        @return x
+        @spawn x: i32
+    }
+
+    proc h() {
+        @modify x {
+    }
+}
+
+data B {}
+";
+        let errors = parse_errors(program);
+        assert_eq!(errors.len(), 2);
+        assert!(format!("{}", errors[0]).ends_with("(at test.def:7:5)"));
+        assert!(format!("{}", errors[1]).ends_with("(at test.def:19:1)"));
+    }
+
+    #[test]
+    fn unterminated_comment() {
+        let program = "
+unit test;
+
+data A {}
+
+/* comment /* nested */
+data B {}
+";
+        let errors = parse_errors(program);
+        assert_eq!(errors.len(), 1);
+        assert!(format!("{}", errors[0]).ends_with("(at test.def:6:1)"));
+    }
+
+    fn loc_to_tuple(loc: &Location) -> ((i32, i32), (i32, i32)) {
+        (loc.begin.unwrap(), loc.end.unwrap())
+    }
+
+    // 別名を持たないimport (from .. import A;) の一覧
+    fn import_items(unit: &Name, targets: Vec<Name>) -> Vec<SysDCImport> {
+        targets
+            .into_iter()
+            .map(|target| {
+                let alias = Name::new(unit, target.name.clone());
+                SysDCImport::new_item(target, alias)
+            })
+            .collect()
+    }
+
     fn generate_name_for_test() -> Name {
         Name::new(&Name::new_root(), "test".to_string())
     }
 
     fn compare_unit(program: &str, unit: SysDCUnit) {
-        assert_eq!(format!("{:?}", parse(program)), format!("{:?}", unit));
+        assert_eq!(
+            format!("{:?}", strip_loc(parse(program))),
+            format!("{:?}", unit)
+        );
+    }
+
+    // 位置情報は別のテストで確認するため，比較前に取り除く
+    fn strip_loc(mut unit: SysDCUnit) -> SysDCUnit {
+        unit.loc = Location::new();
+        for rule in &mut unit.rules {
+            rule.loc = Location::new();
+        }
+        for data in &mut unit.data {
+            data.loc = Location::new();
+        }
+        for _enum in &mut unit.enums {
+            _enum.loc = Location::new();
+            for variant in &mut _enum.variants {
+                variant.loc = Location::new();
+            }
+        }
+        for interface in &mut unit.interfaces {
+            interface.loc = Location::new();
+            for func in &mut interface.functions {
+                func.loc = Location::new();
+            }
+        }
+        for module in &mut unit.modules {
+            module.loc = Location::new();
+            for func in &mut module.functions {
+                func.loc = Location::new();
+            }
+        }
+        unit
     }
 
     fn parse(program: &str) -> SysDCUnit {
@@ -1218,4 +2635,10 @@ mod test {
         let tokenizer = Tokenizer::new("test.def".to_string(), &program);
         UnitParser::parse(tokenizer).unwrap()
     }
+
+    fn parse_errors(program: &str) -> Vec<anyhow::Error> {
+        let program = program.to_string();
+        let tokenizer = Tokenizer::new("test.def".to_string(), &program);
+        UnitParser::parse(tokenizer).unwrap_err()
+    }
 }
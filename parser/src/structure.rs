@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use super::location::Location;
 use super::name::Name;
 use super::types::Type;
 
@@ -12,33 +13,95 @@ pub struct SysDCSystem {
 pub struct SysDCUnit {
     pub name: Name,
     pub data: Vec<SysDCData>,
+    pub enums: Vec<SysDCEnum>,
+    pub interfaces: Vec<SysDCInterface>,
     pub modules: Vec<SysDCModule>,
+    pub loc: Location,
 }
 
+// 定義(Data, Enum, Interface, Module, 関数)を他のUnitから参照できる範囲
+// ※Privateは定義されたUnitの中からのみ，Restrictedは指定されたUnitとその配下のUnit (pub(app) の app) からのみ参照できる
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub enum Visibility {
+    #[default]
+    Private,
+    Public,
+    Restricted(String),
+}
+
+// ※extendsは合成元のData (data AdminUser = User + { .. } のUser)
+// ※membersには合成元のメンバは含まれない
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SysDCData {
+    pub name: Name,
+    pub visibility: Visibility,
+    pub params: Vec<Name>,
+    pub extends: Vec<Type>,
+    pub members: Vec<(Name, Type)>,
+    pub loc: Location,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SysDCEnum {
+    pub name: Name,
+    pub visibility: Visibility,
+    pub variants: Vec<SysDCVariant>,
+    pub loc: Location,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SysDCVariant {
     pub name: Name,
     pub members: Vec<(Name, Type)>,
+    pub loc: Location,
+}
+
+// ※インターフェースの関数はシグネチャのみを持つ (annotationsは常に空)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SysDCInterface {
+    pub name: Name,
+    pub visibility: Visibility,
+    pub functions: Vec<SysDCFunction>,
+    pub loc: Location,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SysDCModule {
     pub name: Name,
+    pub visibility: Visibility,
+    pub params: Vec<Name>,
+    pub implements: Vec<Name>,
     pub functions: Vec<SysDCFunction>,
+    pub loc: Location,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SysDCFunction {
     pub name: Name,
+    pub visibility: Visibility,
+    pub kind: FunctionKind,
     pub args: Vec<(Name, Type)>,
     pub returns: (Name, Type),
     pub annotations: Vec<SysDCAnnotation>,
+    pub loc: Location,
 }
 
+// 関数として宣言された種類
+// ※Funcは値を返すのみで副作用を持たず，Procは副作用を持つ (値は返さない)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FunctionKind {
+    Func,
+    #[default]
+    Proc,
+}
+
+// ※type_argsは関数呼び出し時にModuleへ与えられた型引数 (Repository<User>.save のUser)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum SysDCAnnotation {
     Affect {
         func: (Name, Type),
+        #[serde(default)]
+        type_args: Vec<Type>,
         args: Vec<(Name, Type)>,
     },
     Modify {
@@ -58,6 +121,8 @@ pub enum SysDCSpawnDetail {
     LetTo {
         name: Name,
         func: (Name, Type),
+        #[serde(default)]
+        type_args: Vec<Type>,
         args: Vec<(Name, Type)>,
     },
 }
@@ -65,8 +130,13 @@ pub enum SysDCSpawnDetail {
 pub mod unchecked {
     use anyhow;
 
+    use super::FunctionKind;
+    use super::Location;
     use super::Name;
-    use super::Type;
+    use super::Type as CheckedType;
+    use super::Visibility;
+    use crate::error::{PError, PErrorKind};
+    use crate::types::unchecked::Type;
 
     #[derive(Debug)]
     pub struct SysDCSystem {
@@ -78,15 +148,19 @@ pub mod unchecked {
             SysDCSystem { units }
         }
 
-        pub fn convert<F>(self, mut converter: F) -> anyhow::Result<super::SysDCSystem>
+        // ※変換に失敗した要素は取り除かれ，エラーはまとめて返される (Unit, Moduleも同様)
+        pub fn convert<F>(self, mut converter: F) -> (super::SysDCSystem, Vec<anyhow::Error>)
         where
-            F: FnMut(SysDCUnit) -> anyhow::Result<super::SysDCUnit>,
+            F: FnMut(SysDCUnit) -> (super::SysDCUnit, Vec<anyhow::Error>),
         {
             let mut units = vec![];
+            let mut errors = vec![];
             for unit in self.units {
-                units.push(converter(unit)?);
+                let (unit, _errors) = converter(unit);
+                units.push(unit);
+                errors.extend(_errors);
             }
-            Ok(super::SysDCSystem { units })
+            (super::SysDCSystem { units }, errors)
         }
     }
 
@@ -94,8 +168,12 @@ pub mod unchecked {
     pub struct SysDCUnit {
         pub name: Name,
         pub data: Vec<SysDCData>,
+        pub enums: Vec<SysDCEnum>,
+        pub interfaces: Vec<SysDCInterface>,
         pub modules: Vec<SysDCModule>,
-        pub imports: Vec<Name>,
+        pub imports: Vec<SysDCImport>,
+        pub rules: Vec<SysDCRule>,
+        pub loc: Location,
     }
 
     impl SysDCUnit {
@@ -103,98 +181,446 @@ pub mod unchecked {
             name: Name,
             data: Vec<SysDCData>,
             modules: Vec<SysDCModule>,
-            imports: Vec<Name>,
+            imports: Vec<SysDCImport>,
         ) -> SysDCUnit {
             SysDCUnit {
                 name,
                 data,
+                enums: vec![],
+                interfaces: vec![],
                 modules,
                 imports,
+                rules: vec![],
+                loc: Location::new(),
             }
         }
 
-        pub fn convert<F, G>(
+        pub fn with_enums(mut self, enums: Vec<SysDCEnum>) -> SysDCUnit {
+            self.enums = enums;
+            self
+        }
+
+        pub fn with_interfaces(mut self, interfaces: Vec<SysDCInterface>) -> SysDCUnit {
+            self.interfaces = interfaces;
+            self
+        }
+
+        pub fn with_rules(mut self, rules: Vec<SysDCRule>) -> SysDCUnit {
+            self.rules = rules;
+            self
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCUnit {
+            self.loc = loc;
+            self
+        }
+
+        pub fn convert<F, G, H, I>(
             self,
             d_converter: F,
-            m_converter: G,
-        ) -> anyhow::Result<super::SysDCUnit>
+            e_converter: G,
+            i_converter: H,
+            m_converter: I,
+        ) -> (super::SysDCUnit, Vec<anyhow::Error>)
         where
             F: Fn(SysDCData) -> anyhow::Result<super::SysDCData>,
-            G: Fn(SysDCModule) -> anyhow::Result<super::SysDCModule>,
+            G: Fn(SysDCEnum) -> anyhow::Result<super::SysDCEnum>,
+            H: Fn(SysDCInterface) -> (super::SysDCInterface, Vec<anyhow::Error>),
+            I: Fn(SysDCModule) -> (super::SysDCModule, Vec<anyhow::Error>),
         {
-            let (mut data, mut modules) = (vec![], vec![]);
+            let (mut data, mut enums, mut interfaces, mut modules) =
+                (vec![], vec![], vec![], vec![]);
+            let mut errors = vec![];
             for _data in self.data {
-                data.push(d_converter(_data)?);
+                match d_converter(_data) {
+                    Ok(_data) => data.push(_data),
+                    Err(err) => errors.push(err),
+                }
+            }
+            for _enum in self.enums {
+                match e_converter(_enum) {
+                    Ok(_enum) => enums.push(_enum),
+                    Err(err) => errors.push(err),
+                }
+            }
+            for interface in self.interfaces {
+                let (interface, _errors) = i_converter(interface);
+                interfaces.push(interface);
+                errors.extend(_errors);
             }
             for module in self.modules {
-                modules.push(m_converter(module)?);
+                let (module, _errors) = m_converter(module);
+                modules.push(module);
+                errors.extend(_errors);
             }
-            Ok(super::SysDCUnit {
+            let unit = super::SysDCUnit {
                 name: self.name,
                 data,
+                enums,
+                interfaces,
                 modules,
-            })
+                loc: self.loc,
+            };
+            (unit, errors)
+        }
+    }
+
+    // ※targetはimportされる定義 (from std.io import IO の .0.std.io.IO) またはUnit (import std.time の .0.std.time)
+    // ※aliasはUnit内でtargetを参照するための名前 (asで指定されなかった場合はtargetの名前の末尾)
+    #[derive(Debug, Clone)]
+    pub enum SysDCImport {
+        Item { target: Name, alias: Name },
+        Wildcard(Name),
+        Unit { target: Name, alias: Name },
+    }
+
+    impl SysDCImport {
+        pub fn new_item(target: Name, alias: Name) -> SysDCImport {
+            SysDCImport::Item { target, alias }
+        }
+
+        pub fn new_wildcard(target: Name) -> SysDCImport {
+            SysDCImport::Wildcard(target)
+        }
+
+        pub fn new_unit(target: Name, alias: Name) -> SysDCImport {
+            SysDCImport::Unit { target, alias }
+        }
+    }
+
+    // Unit間の依存に関する規則 (rule { deny domain.* -> infra.*; })
+    // ※sourceに一致するUnitから，targetに一致するUnitへの依存(import, @affect, letでの関数呼び出し)を禁止する
+    // ※規則はそれを記述したUnitに限らず，すべてのUnitに適用される
+    #[derive(Debug, Clone)]
+    pub struct SysDCRule {
+        pub source: SysDCUnitPattern,
+        pub target: SysDCUnitPattern,
+        pub loc: Location,
+    }
+
+    impl SysDCRule {
+        pub fn new(source: SysDCUnitPattern, target: SysDCUnitPattern) -> SysDCRule {
+            SysDCRule {
+                source,
+                target,
+                loc: Location::new(),
+            }
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCRule {
+            self.loc = loc;
+            self
+        }
+    }
+
+    // ※sub_unitsがtrueの場合は，nameのUnitとその配下のUnit (domain.* の domain, domain.user, ..) に一致する
+    #[derive(Debug, Clone)]
+    pub struct SysDCUnitPattern {
+        pub name: Name,
+        pub sub_units: bool,
+    }
+
+    impl SysDCUnitPattern {
+        pub fn new(name: Name, sub_units: bool) -> SysDCUnitPattern {
+            SysDCUnitPattern { name, sub_units }
+        }
+
+        // unitはUnitの完全な名前
+        pub fn matches(&self, unit: &str) -> bool {
+            let name = self.name.get_full_name();
+            unit == name || (self.sub_units && unit.starts_with(&format!("{}.", name)))
         }
     }
 
     #[derive(Debug)]
     pub struct SysDCData {
         pub name: Name,
+        pub visibility: Visibility,
+        pub params: Vec<Name>,
+        pub extends: Vec<(Name, Type)>,
         pub members: Vec<(Name, Type)>,
+        pub loc: Location,
     }
 
     impl SysDCData {
         pub fn new(name: Name, members: Vec<(Name, Type)>) -> SysDCData {
-            SysDCData { name, members }
+            SysDCData {
+                name,
+                visibility: Visibility::default(),
+                params: vec![],
+                extends: vec![],
+                members,
+                loc: Location::new(),
+            }
         }
 
-        pub fn convert<F>(self, converter: F) -> anyhow::Result<super::SysDCData>
+        pub fn with_params(mut self, params: Vec<Name>) -> SysDCData {
+            self.params = params;
+            self
+        }
+
+        pub fn with_extends(mut self, extends: Vec<(Name, Type)>) -> SysDCData {
+            self.extends = extends;
+            self
+        }
+
+        pub fn with_visibility(mut self, visibility: Visibility) -> SysDCData {
+            self.visibility = visibility;
+            self
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCData {
+            self.loc = loc;
+            self
+        }
+
+        pub fn convert<F, G>(
+            self,
+            e_converter: F,
+            m_converter: G,
+        ) -> anyhow::Result<super::SysDCData>
         where
-            F: Fn((Name, Type)) -> anyhow::Result<(Name, Type)>,
+            F: Fn((Name, Type)) -> anyhow::Result<CheckedType>,
+            G: Fn((Name, Type)) -> anyhow::Result<(Name, CheckedType)>,
         {
+            let mut extends = vec![];
+            for base in self.extends {
+                extends.push(e_converter(base)?);
+            }
             let mut members = vec![];
             for member in self.members {
-                members.push(converter(member)?);
+                members.push(m_converter(member)?);
             }
             Ok(super::SysDCData {
                 name: self.name,
+                visibility: self.visibility,
+                params: self.params,
+                extends,
+                members,
+                loc: self.loc,
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SysDCEnum {
+        pub name: Name,
+        pub visibility: Visibility,
+        pub variants: Vec<SysDCVariant>,
+        pub loc: Location,
+    }
+
+    impl SysDCEnum {
+        pub fn new(name: Name, variants: Vec<SysDCVariant>) -> SysDCEnum {
+            SysDCEnum {
+                name,
+                visibility: Visibility::default(),
+                variants,
+                loc: Location::new(),
+            }
+        }
+
+        pub fn with_visibility(mut self, visibility: Visibility) -> SysDCEnum {
+            self.visibility = visibility;
+            self
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCEnum {
+            self.loc = loc;
+            self
+        }
+
+        pub fn convert<F>(self, converter: F) -> anyhow::Result<super::SysDCEnum>
+        where
+            F: Fn(SysDCVariant) -> anyhow::Result<super::SysDCVariant>,
+        {
+            let mut variants = vec![];
+            for variant in self.variants {
+                variants.push(converter(variant)?);
+            }
+            Ok(super::SysDCEnum {
+                name: self.name,
+                visibility: self.visibility,
+                variants,
+                loc: self.loc,
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SysDCVariant {
+        pub name: Name,
+        pub members: Vec<(Name, Type)>,
+        pub loc: Location,
+    }
+
+    impl SysDCVariant {
+        pub fn new(name: Name, members: Vec<(Name, Type)>) -> SysDCVariant {
+            SysDCVariant {
+                name,
                 members,
+                loc: Location::new(),
+            }
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCVariant {
+            self.loc = loc;
+            self
+        }
+
+        pub fn convert<F>(self, converter: F) -> anyhow::Result<super::SysDCVariant>
+        where
+            F: Fn((Name, Type)) -> anyhow::Result<(Name, CheckedType)>,
+        {
+            let mut members = vec![];
+            for member in self.members {
+                members.push(converter(member)?);
+            }
+            Ok(super::SysDCVariant {
+                name: self.name,
+                members,
+                loc: self.loc,
             })
         }
     }
 
+    #[derive(Debug)]
+    pub struct SysDCInterface {
+        pub name: Name,
+        pub visibility: Visibility,
+        pub functions: Vec<SysDCFunction>,
+        pub loc: Location,
+    }
+
+    impl SysDCInterface {
+        pub fn new(name: Name, functions: Vec<SysDCFunction>) -> SysDCInterface {
+            SysDCInterface {
+                name,
+                visibility: Visibility::default(),
+                functions,
+                loc: Location::new(),
+            }
+        }
+
+        pub fn with_visibility(mut self, visibility: Visibility) -> SysDCInterface {
+            self.visibility = visibility;
+            self
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCInterface {
+            self.loc = loc;
+            self
+        }
+
+        pub fn convert<F>(self, converter: F) -> (super::SysDCInterface, Vec<anyhow::Error>)
+        where
+            F: Fn(SysDCFunction) -> anyhow::Result<super::SysDCFunction>,
+        {
+            let mut functions = vec![];
+            let mut errors = vec![];
+            for func in self.functions {
+                match converter(func) {
+                    Ok(func) => functions.push(func),
+                    Err(err) => errors.push(err),
+                }
+            }
+            let interface = super::SysDCInterface {
+                name: self.name,
+                visibility: self.visibility,
+                functions,
+                loc: self.loc,
+            };
+            (interface, errors)
+        }
+    }
+
     #[derive(Debug)]
     pub struct SysDCModule {
         pub name: Name,
+        pub visibility: Visibility,
+        pub params: Vec<Name>,
+        pub implements: Vec<Name>,
         pub functions: Vec<SysDCFunction>,
+        pub loc: Location,
     }
 
     impl SysDCModule {
         pub fn new(name: Name, functions: Vec<SysDCFunction>) -> SysDCModule {
-            SysDCModule { name, functions }
+            SysDCModule {
+                name,
+                visibility: Visibility::default(),
+                params: vec![],
+                implements: vec![],
+                functions,
+                loc: Location::new(),
+            }
         }
 
-        pub fn convert<F>(self, converter: F) -> anyhow::Result<super::SysDCModule>
+        pub fn with_params(mut self, params: Vec<Name>) -> SysDCModule {
+            self.params = params;
+            self
+        }
+
+        pub fn with_implements(mut self, implements: Vec<Name>) -> SysDCModule {
+            self.implements = implements;
+            self
+        }
+
+        pub fn with_visibility(mut self, visibility: Visibility) -> SysDCModule {
+            self.visibility = visibility;
+            self
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCModule {
+            self.loc = loc;
+            self
+        }
+
+        pub fn convert<F, G>(
+            self,
+            i_converter: F,
+            f_converter: G,
+        ) -> (super::SysDCModule, Vec<anyhow::Error>)
         where
-            F: Fn(SysDCFunction) -> anyhow::Result<super::SysDCFunction>,
+            F: Fn(Name) -> anyhow::Result<Name>,
+            G: Fn(SysDCFunction) -> anyhow::Result<super::SysDCFunction>,
         {
+            let mut implements = vec![];
             let mut functions = vec![];
+            let mut errors = vec![];
+            for interface in self.implements {
+                match i_converter(interface) {
+                    Ok(interface) => implements.push(interface),
+                    Err(err) => errors.push(err),
+                }
+            }
             for func in self.functions {
-                functions.push(converter(func)?);
+                match f_converter(func) {
+                    Ok(func) => functions.push(func),
+                    Err(err) => errors.push(err),
+                }
             }
-            Ok(super::SysDCModule {
+            let module = super::SysDCModule {
                 name: self.name,
+                visibility: self.visibility,
+                params: self.params,
+                implements,
                 functions,
-            })
+                loc: self.loc,
+            };
+            (module, errors)
         }
     }
 
     #[derive(Debug)]
     pub struct SysDCFunction {
         pub name: Name,
+        pub visibility: Visibility,
+        pub kind: FunctionKind,
         pub args: Vec<(Name, Type)>,
         pub returns: (Name, Type),
         pub annotations: Vec<SysDCAnnotation>,
+        pub loc: Location,
     }
 
     impl SysDCFunction {
@@ -206,12 +632,30 @@ pub mod unchecked {
         ) -> SysDCFunction {
             SysDCFunction {
                 name,
+                visibility: Visibility::default(),
+                kind: FunctionKind::default(),
                 args,
                 returns,
                 annotations,
+                loc: Location::new(),
             }
         }
 
+        pub fn with_visibility(mut self, visibility: Visibility) -> SysDCFunction {
+            self.visibility = visibility;
+            self
+        }
+
+        pub fn with_kind(mut self, kind: FunctionKind) -> SysDCFunction {
+            self.kind = kind;
+            self
+        }
+
+        pub fn with_loc(mut self, loc: Location) -> SysDCFunction {
+            self.loc = loc;
+            self
+        }
+
         pub fn convert<F, G, H>(
             self,
             a_convert: F,
@@ -219,8 +663,8 @@ pub mod unchecked {
             s_convert: H,
         ) -> anyhow::Result<super::SysDCFunction>
         where
-            F: Fn((Name, Type)) -> anyhow::Result<(Name, Type)>,
-            G: Fn((Name, Type)) -> anyhow::Result<(Name, Type)>,
+            F: Fn((Name, Type)) -> anyhow::Result<(Name, CheckedType)>,
+            G: Fn((Name, Type)) -> anyhow::Result<(Name, CheckedType)>,
             H: Fn(SysDCAnnotation) -> anyhow::Result<super::SysDCAnnotation>,
         {
             let returns = r_convert(self.returns)?;
@@ -234,9 +678,12 @@ pub mod unchecked {
             }
             Ok(super::SysDCFunction {
                 name: self.name,
+                visibility: self.visibility,
+                kind: self.kind,
                 args,
                 returns,
                 annotations,
+                loc: self.loc,
             })
         }
     }
@@ -285,20 +732,29 @@ pub mod unchecked {
             F: Fn(
                 (Name, Type),
                 Vec<(Name, Type)>,
-            ) -> anyhow::Result<((Name, Type), Vec<(Name, Type)>)>,
+            ) -> anyhow::Result<(
+                (Name, CheckedType),
+                Vec<CheckedType>,
+                Vec<(Name, CheckedType)>,
+            )>,
             G: Fn(
                 (Name, Type),
                 Vec<(Name, Type)>,
-            ) -> anyhow::Result<((Name, Type), Vec<(Name, Type)>)>,
+            ) -> anyhow::Result<((Name, CheckedType), Vec<(Name, CheckedType)>)>,
             H: Fn(
                 (Name, Type),
                 Vec<SysDCSpawnDetail>,
-            ) -> anyhow::Result<((Name, Type), Vec<super::SysDCSpawnDetail>)>,
+            )
+                -> anyhow::Result<((Name, CheckedType), Vec<super::SysDCSpawnDetail>)>,
         {
             match self {
                 SysDCAnnotation::Affect { func, args } => {
-                    let (func, args) = a_converter(func, args)?;
-                    Ok(super::SysDCAnnotation::Affect { func, args })
+                    let (func, type_args, args) = a_converter(func, args)?;
+                    Ok(super::SysDCAnnotation::Affect {
+                        func,
+                        type_args,
+                        args,
+                    })
                 }
                 SysDCAnnotation::Modify { target, uses } => {
                     let (target, uses) = m_converter(target, uses)?;
@@ -308,7 +764,12 @@ pub mod unchecked {
                     let (result, details) = s_converter(result, details)?;
                     Ok(super::SysDCAnnotation::Spawn { result, details })
                 }
-                _ => panic!("Internal error"),
+                // ※returnは構文解析時に関数の返り値として取り除かれる
+                SysDCAnnotation::Return(name) => Err(PError::from(PErrorKind::InternalError(
+                    "annotation \"return\" remains in function body".to_string(),
+                ))
+                .with_loc(name.loc)
+                .into()),
             }
         }
     }
@@ -348,12 +809,17 @@ pub mod unchecked {
             l_converter: G,
         ) -> anyhow::Result<super::SysDCSpawnDetail>
         where
-            F: Fn((Name, Type)) -> anyhow::Result<(Name, Type)>,
+            F: Fn((Name, Type)) -> anyhow::Result<(Name, CheckedType)>,
             G: Fn(
                 Name,
                 (Name, Type),
                 Vec<(Name, Type)>,
-            ) -> anyhow::Result<(Name, (Name, Type), Vec<(Name, Type)>)>,
+            ) -> anyhow::Result<(
+                Name,
+                (Name, CheckedType),
+                Vec<CheckedType>,
+                Vec<(Name, CheckedType)>,
+            )>,
         {
             match self {
                 SysDCSpawnDetail::Use(name, types) => {
@@ -365,8 +831,13 @@ pub mod unchecked {
                     Ok(super::SysDCSpawnDetail::Return(name, types))
                 }
                 SysDCSpawnDetail::LetTo { name, func, args } => {
-                    let (name, func, args) = l_converter(name, func, args)?;
-                    Ok(super::SysDCSpawnDetail::LetTo { name, func, args })
+                    let (name, func, type_args, args) = l_converter(name, func, args)?;
+                    Ok(super::SysDCSpawnDetail::LetTo {
+                        name,
+                        func,
+                        type_args,
+                        args,
+                    })
                 }
             }
         }
@@ -1,58 +1,80 @@
 use std::str::Chars;
 
+use unicode_xid::UnicodeXID;
+
 use super::error::{PError, PErrorKind};
 use super::location::Location;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TokenKind {
     /* Reserved */
-    Unit,   // unit
-    From,   // from
-    Import, // import
-    Data,   // data
-    Module, // module
-    Func,   // func
-    Proc,   // proc
-    Return, // return
-    Affect, // affect
-    Modify, // modify
-    Spawn,  // spawn
-    Let,    // let
-    Use,    // use
+    Unit,       // unit
+    From,       // from
+    Import,     // import
+    Data,       // data
+    Enum,       // enum
+    Interface,  // interface
+    Module,     // module
+    Implements, // implements
+    Pub,        // pub
+    As,         // as
+    Rule,       // rule
+    Deny,       // deny
+    Func,       // func
+    Proc,       // proc
+    Return,     // return
+    Affect,     // affect
+    Modify,     // modify
+    Spawn,      // spawn
+    Let,        // let
+    Use,        // use
 
     /* Symbol */
-    Allow,            // ->
-    Mapping,          // :
-    Equal,            // =
-    Accessor,         // .
-    Separater,        // ,
-    Semicolon,        // ;
-    ParenthesisBegin, // (
-    ParenthesisEnd,   // )
-    BracketBegin,     // {
-    BracketEnd,       // }
-    AtMark,           // @
-    Plus,             // +
+    Allow,             // ->
+    Mapping,           // :
+    Equal,             // =
+    Accessor,          // .
+    Separater,         // ,
+    Semicolon,         // ;
+    ParenthesisBegin,  // (
+    ParenthesisEnd,    // )
+    BracketBegin,      // {
+    BracketEnd,        // }
+    AngleBracketBegin, // <
+    AngleBracketEnd,   // >
+    AtMark,            // @
+    Plus,              // +
+    Asterisk,          // *
 
     /* Others */
     Identifier,
 }
 
+// ※docはトークンの直前に記述されたドキュメントコメント (%% ... %%)
 #[derive(Debug, Clone)]
 pub struct Token {
     pub kind: TokenKind,
     pub orig: String,
     pub location: Location,
+    pub doc: Option<String>,
 }
 
 impl Token {
+    // (row, col)はトークンの開始位置を表す
     pub fn new(orig: String, filename: String, row: i32, col: i32) -> Token {
         let kind = match orig.as_str() {
             "unit" => TokenKind::Unit,
             "from" => TokenKind::From,
             "import" => TokenKind::Import,
             "data" => TokenKind::Data,
+            "enum" => TokenKind::Enum,
+            "interface" => TokenKind::Interface,
             "module" => TokenKind::Module,
+            "implements" => TokenKind::Implements,
+            "pub" => TokenKind::Pub,
+            "as" => TokenKind::As,
+            "rule" => TokenKind::Rule,
+            "deny" => TokenKind::Deny,
             "func" => TokenKind::Func,
             "proc" => TokenKind::Proc,
             "return" => TokenKind::Return,
@@ -71,19 +93,29 @@ impl Token {
             ")" => TokenKind::ParenthesisEnd,
             "{" => TokenKind::BracketBegin,
             "}" => TokenKind::BracketEnd,
+            "<" => TokenKind::AngleBracketBegin,
+            ">" => TokenKind::AngleBracketEnd,
             "@" => TokenKind::AtMark,
             "+" => TokenKind::Plus,
+            "*" => TokenKind::Asterisk,
             _ => TokenKind::Identifier,
         };
         let location = Location::new()
             .with_filename(filename)
-            .with_coord((row, col - (orig.len() as i32)));
+            .with_coord((row, col))
+            .with_end_coord((row, col + (orig.chars().count() as i32)));
         Token {
             kind,
             orig,
             location,
+            doc: None,
         }
     }
+
+    pub fn with_doc(mut self, doc: Option<String>) -> Token {
+        self.doc = doc;
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -97,6 +129,15 @@ pub struct Tokenizer<'a> {
     filename: String,
     now_ref_row: i32,
     now_ref_col: i32,
+
+    // 読み進めたトークン列における{}のネストの深さ (エラーからの復帰に使用)
+    depth: u32,
+
+    // 読み飛ばしたドキュメントコメント (次に生成されるトークンに付与される)
+    doc: Option<String>,
+
+    // 閉じられていないコメントの開始位置
+    unterminated_comment: Option<Location>,
 }
 
 impl<'a> Tokenizer<'a> {
@@ -109,6 +150,9 @@ impl<'a> Tokenizer<'a> {
             filename,
             now_ref_row: 1,
             now_ref_col: 1,
+            depth: 0,
+            doc: None,
+            unterminated_comment: None,
         };
         tokenizer.skip_space();
         tokenizer
@@ -119,7 +163,7 @@ impl<'a> Tokenizer<'a> {
             Some(token) => token.location.clone(),
             None => Location::new()
                 .with_filename(self.filename.clone())
-                .with_coord((self.now_ref_row, self.now_ref_col)),
+                .with_coord((self.now_ref_row, self.now_ref_col - 1)),
         }
     }
 
@@ -134,11 +178,14 @@ impl<'a> Tokenizer<'a> {
         self.hold_char.is_some()
     }
 
+    pub fn get_depth(&self) -> u32 {
+        self.depth
+    }
+
     pub fn expect(&mut self, kind: TokenKind) -> anyhow::Result<Option<Token>> {
         if let Some(token) = self.tokenize()? {
             if token.kind == kind {
-                self.hold_token = None;
-                Ok(Some(token))
+                Ok(Some(self.consume(token)))
             } else {
                 self.hold_token = Some(token);
                 Ok(None)
@@ -148,6 +195,21 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    // 次のトークンの種類を返す (トークンは消費されない)
+    pub fn peek(&mut self) -> anyhow::Result<Option<TokenKind>> {
+        let token = self.tokenize()?;
+        self.hold_token = token.clone();
+        Ok(token.map(|token| token.kind))
+    }
+
+    // 次のトークンを種類に関わらず消費する
+    pub fn skip(&mut self) -> anyhow::Result<Option<Token>> {
+        match self.tokenize()? {
+            Some(token) => Ok(Some(self.consume(token))),
+            None => Ok(None),
+        }
+    }
+
     pub fn request(&mut self, kind: TokenKind) -> anyhow::Result<Token> {
         match self.expect(kind.clone())? {
             Some(token) => Ok(token),
@@ -157,20 +219,50 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    fn consume(&mut self, token: Token) -> Token {
+        self.hold_token = None;
+        match token.kind {
+            TokenKind::BracketBegin => self.depth += 1,
+            TokenKind::BracketEnd => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        token
+    }
+
     fn tokenize(&mut self) -> anyhow::Result<Option<Token>> {
         if self.hold_token.is_some() {
             return Ok(self.hold_token.clone());
         }
+        if let Some(loc) = self.unterminated_comment.take() {
+            return Err(PError::from(PErrorKind::UnterminatedComment)
+                .with_loc(loc)
+                .into());
+        }
         if !self.exists_next() {
             return Ok(None);
         }
 
+        // ※hold_charの位置は(now_ref_row, now_ref_col-1)
+        let (row, col) = (self.now_ref_row, self.now_ref_col - 1);
         let lead_type = CharType::from(self.hold_char.unwrap());
+        if let CharType::IdentifierContinue | CharType::Other = lead_type {
+            let err = PError::from(PErrorKind::UnexpectedCharacter(self.hold_char.unwrap()))
+                .with_loc(self.get_now_ref_loc());
+
+            // 不明な文字を読み捨てて，次のトークンから再開できるようにする
+            self.hold_char = None;
+            self.skip_space();
+            return Err(err.into());
+        }
+        let doc = self.doc.take();
         self.adopt()?;
         while self.exists_next() {
             match (&lead_type, CharType::from(self.hold_char.unwrap())) {
                 // Ok(continue)
-                (CharType::Identifier, CharType::Identifier | CharType::Number) => {}
+                (
+                    CharType::Identifier,
+                    CharType::Identifier | CharType::IdentifierContinue | CharType::Number,
+                ) => {}
                 (CharType::Number, CharType::Number) => {}
 
                 // Ok(force stop)
@@ -179,12 +271,17 @@ impl<'a> Tokenizer<'a> {
                     self.adopt()?;
                     break;
                 }
+                (CharType::SymbolAllow2, _) => break,
 
                 // Ng(panic)
-                (CharType::SymbolAllow1 | CharType::SymbolAllow2, _) => {
-                    return Err(PError::from(PErrorKind::FoundUnregisteredSymbol)
-                        .with_loc(self.get_now_ref_loc())
-                        .into())
+                (CharType::SymbolAllow1, _) => {
+                    let err = PError::from(PErrorKind::FoundUnregisteredSymbol)
+                        .with_loc(self.get_now_ref_loc());
+
+                    // 読み込み途中の文字を破棄して，次のトークンから再開できるようにする
+                    self.hold_chars.clear();
+                    self.skip_space();
+                    return Err(err.into());
                 }
 
                 // Ok(force stop)
@@ -194,12 +291,9 @@ impl<'a> Tokenizer<'a> {
         }
         self.skip_space();
 
-        Ok(Some(Token::new(
-            self.collect(),
-            self.filename.to_string(),
-            self.now_ref_row,
-            self.now_ref_col,
-        )))
+        Ok(Some(
+            Token::new(self.collect(), self.filename.to_string(), row, col).with_doc(doc),
+        ))
     }
 
     fn adopt(&mut self) -> anyhow::Result<()> {
@@ -222,39 +316,114 @@ impl<'a> Tokenizer<'a> {
         result
     }
 
+    // 空白とコメントを読み飛ばす
+    // ※%% ... %% はドキュメントコメントとして，内容を次のトークンのために保持する
+    // ※閉じられないままファイル末尾に達したコメントは，次のトークン分割時にエラーとして報告する
+    //   (行コメントはファイル末尾で終わってもよい)
     fn skip_space(&mut self) {
-        let mut comment = false;
+        let mut comment: Option<(Comment, Location)> = None;
         while self.exists_next() {
-            match CharType::from(self.hold_char.unwrap()) {
-                CharType::Space => {}
-                CharType::NewLine => {
-                    self.now_ref_row += 1;
-                    self.now_ref_col = 1;
+            let c = self.hold_char.unwrap();
+            let next = self.chars.clone().next();
+            if let CharType::NewLine = CharType::from(c) {
+                self.now_ref_row += 1;
+                self.now_ref_col = 1;
+            }
+            match (&mut comment, c, next) {
+                (None, '%', Some('%')) => {
+                    comment = Some((Comment::Doc(String::new()), self.get_now_char_loc()));
+                    self.skip_next_char();
                 }
-                CharType::Comment => {
-                    comment = !comment;
+                (None, '%', _) => comment = Some((Comment::Toggle, self.get_now_char_loc())),
+                (None, '/', Some('/')) => {
+                    comment = Some((Comment::Line, self.get_now_char_loc()));
+                    self.skip_next_char();
                 }
-                _ => {
-                    if !comment {
-                        break;
+                (None, '/', Some('*')) => {
+                    comment = Some((Comment::Block(1), self.get_now_char_loc()));
+                    self.skip_next_char();
+                }
+                (None, c, _)
+                    if !matches!(CharType::from(c), CharType::Space | CharType::NewLine) =>
+                {
+                    break;
+                }
+                (Some((Comment::Toggle, _)), '%', _) => comment = None,
+                (Some((Comment::Doc(text), _)), '%', Some('%')) => {
+                    let text = std::mem::take(text);
+                    self.push_doc(&text);
+                    self.skip_next_char();
+                    comment = None;
+                }
+                (Some((Comment::Doc(text), _)), c, _) => text.push(c),
+                (Some((Comment::Line, _)), '\n', _) => comment = None,
+                (Some((Comment::Block(depth), _)), '/', Some('*')) => {
+                    *depth += 1;
+                    self.skip_next_char();
+                }
+                (Some((Comment::Block(depth), _)), '*', Some('/')) => {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        comment = None;
                     }
+                    self.skip_next_char();
                 }
+                _ => {}
             }
             self.hold_char = None;
         }
+        match comment {
+            Some((Comment::Line, _)) | None => {}
+            Some((_, loc)) => self.unterminated_comment = Some(loc),
+        }
     }
+
+    // hold_charの次の文字を読み飛ばす
+    fn skip_next_char(&mut self) {
+        self.chars.next();
+        self.now_ref_col += 1;
+    }
+
+    // hold_charの位置
+    fn get_now_char_loc(&self) -> Location {
+        Location::new()
+            .with_filename(self.filename.clone())
+            .with_coord((self.now_ref_row, self.now_ref_col - 1))
+    }
+
+    // 複数のドキュメントコメントが続く場合は改行で連結する
+    fn push_doc(&mut self, text: &str) {
+        let text = text
+            .lines()
+            .map(|line| line.trim())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let text = text.trim();
+        self.doc = match self.doc.take() {
+            Some(doc) => Some(format!("{}\n{}", doc, text)),
+            None => Some(text.to_string()),
+        };
+    }
+}
+
+#[derive(Debug)]
+enum Comment {
+    Toggle,      // % ... %
+    Doc(String), // %% ... %%
+    Line,        // // ...
+    Block(u32),  // /* ... */ (ネストの深さ)
 }
 
 #[derive(Debug)]
 enum CharType {
     Number,
     Identifier,
+    IdentifierContinue, // 識別子の2文字目以降にのみ現れる文字 (結合文字など)
 
     Symbol,
     SymbolAllow1,
     SymbolAllow2,
 
-    Comment,
     Space,
     NewLine,
 
@@ -265,13 +434,16 @@ impl From<char> for CharType {
     fn from(c: char) -> CharType {
         match c {
             '0'..='9' => CharType::Number,
-            'a'..='z' | 'A'..='Z' | '_' => CharType::Identifier,
+            '_' => CharType::Identifier,
+            c if c.is_xid_start() => CharType::Identifier,
+            c if c.is_xid_continue() => CharType::IdentifierContinue,
 
-            '=' | '.' | ',' | ';' | '{' | '}' | '(' | ')' | ':' => CharType::Symbol,
+            '=' | '.' | ',' | ';' | '{' | '}' | '(' | ')' | ':' | '<' | '@' | '+' | '*' => {
+                CharType::Symbol
+            }
             '-' => CharType::SymbolAllow1,
             '>' => CharType::SymbolAllow2,
 
-            '%' => CharType::Comment,
             ' ' | '\t' | '\r' => CharType::Space,
             '\n' => CharType::NewLine,
 
@@ -292,7 +464,14 @@ mod test {
                 ("from", TokenKind::From),
                 ("import", TokenKind::Import),
                 ("data", TokenKind::Data),
+                ("enum", TokenKind::Enum),
+                ("interface", TokenKind::Interface),
                 ("module", TokenKind::Module),
+                ("implements", TokenKind::Implements),
+                ("pub", TokenKind::Pub),
+                ("as", TokenKind::As),
+                ("rule", TokenKind::Rule),
+                ("deny", TokenKind::Deny),
                 ("return", TokenKind::Return),
                 ("affect", TokenKind::Affect),
                 ("modify", TokenKind::Modify),
@@ -309,8 +488,11 @@ mod test {
                 (")", TokenKind::ParenthesisEnd),
                 ("{", TokenKind::BracketBegin),
                 ("}", TokenKind::BracketEnd),
+                ("<", TokenKind::AngleBracketBegin),
+                (">", TokenKind::AngleBracketEnd),
                 ("@", TokenKind::AtMark),
                 ("+", TokenKind::Plus),
+                ("*", TokenKind::Asterisk),
             ];
             for (_str, kind) in str_kind_mapping {
                 assert_eq!(
@@ -329,6 +511,7 @@ mod test {
 
     mod tokenizer {
         use super::super::{TokenKind, Tokenizer};
+        use crate::error::{PError, PErrorKind};
 
         #[test]
         pub fn create_tokenizer() {
@@ -464,11 +647,158 @@ mod test {
             for token_kind in correct_token_kinds {
                 match tokenizer.expect(token_kind.clone()).unwrap() {
                     Some(_) => {}
-                    None => assert!(false, "{:?}", token_kind),
+                    None => panic!("{:?}", token_kind),
                 }
             }
         }
 
+        #[test]
+        fn expect_generic_type() {
+            let text = "Map<i32, List<Box>>->".to_string();
+            let correct_token_kinds = [
+                TokenKind::Identifier,
+                TokenKind::AngleBracketBegin,
+                TokenKind::Identifier,
+                TokenKind::Separater,
+                TokenKind::Identifier,
+                TokenKind::AngleBracketBegin,
+                TokenKind::Identifier,
+                TokenKind::AngleBracketEnd,
+                TokenKind::AngleBracketEnd,
+                TokenKind::Allow,
+            ];
+
+            let mut tokenizer = Tokenizer::new("test.def".to_string(), &text);
+            for token_kind in correct_token_kinds {
+                let token = tokenizer.request(token_kind.clone()).unwrap();
+                assert_eq!(token.kind, token_kind);
+            }
+            assert!(!tokenizer.exists_next());
+        }
+
+        #[test]
+        fn expect_doc_comment() {
+            let text = "
+                %% Boxを表す
+                   Data %%
+                % comment %
+                %% 2行目 %%
+                data Box { % comment % x: i32 }
+            "
+            .to_string();
+
+            let mut tokenizer = Tokenizer::new("test.def".to_string(), &text);
+            let token = tokenizer.request(TokenKind::Data).unwrap();
+            assert_eq!(token.doc, Some("Boxを表す\nData\n2行目".to_string()));
+            let token = tokenizer.request(TokenKind::Identifier).unwrap();
+            assert_eq!(token.doc, None);
+            tokenizer.request(TokenKind::BracketBegin).unwrap();
+            let token = tokenizer.request(TokenKind::Identifier).unwrap();
+            assert_eq!(token.doc, None);
+        }
+
+        #[test]
+        fn expect_line_and_block_comment() {
+            let text = "
+                // comment % /*
+                data /* comment /* nested */ */ Box // comment
+                { /* x: i32 */ }
+            "
+            .to_string();
+            let correct_token_kinds = [
+                TokenKind::Data,
+                TokenKind::Identifier,
+                TokenKind::BracketBegin,
+                TokenKind::BracketEnd,
+            ];
+
+            let mut tokenizer = Tokenizer::new("test.def".to_string(), &text);
+            for token_kind in correct_token_kinds {
+                let token = tokenizer.request(token_kind.clone()).unwrap();
+                assert_eq!(token.kind, token_kind);
+            }
+            assert!(!tokenizer.exists_next());
+
+            let text = "data // comment".to_string();
+            let mut tokenizer = Tokenizer::new("test.def".to_string(), &text);
+            tokenizer.request(TokenKind::Data).unwrap();
+            assert!(tokenizer.peek().unwrap().is_none());
+        }
+
+        #[test]
+        fn expect_unterminated_comment() {
+            let texts = [
+                ("data /* comment /* nested */", "test.def:1:6"),
+                ("data\n  % comment", "test.def:2:3"),
+                ("data %% doc", "test.def:1:6"),
+            ];
+            for (text, loc) in texts {
+                let mut tokenizer = Tokenizer::new("test.def".to_string(), text);
+                tokenizer.request(TokenKind::Data).unwrap();
+                let err = tokenizer.peek().unwrap_err().downcast::<PError>().unwrap();
+                assert!(matches!(err.kind(), PErrorKind::UnterminatedComment));
+                assert_eq!(format!("{}", err.location()), loc);
+                assert!(tokenizer.peek().unwrap().is_none());
+            }
+        }
+
+        #[test]
+        fn expect_unicode_identifier() {
+            let text = "data 箱 { 幅_1: i32, café: i32, _x: i32 }".to_string();
+            let correct_tokens = [
+                (TokenKind::Data, "data"),
+                (TokenKind::Identifier, "箱"),
+                (TokenKind::BracketBegin, "{"),
+                (TokenKind::Identifier, "幅_1"),
+                (TokenKind::Mapping, ":"),
+                (TokenKind::Identifier, "i32"),
+                (TokenKind::Separater, ","),
+                (TokenKind::Identifier, "café"),
+                (TokenKind::Mapping, ":"),
+                (TokenKind::Identifier, "i32"),
+                (TokenKind::Separater, ","),
+                (TokenKind::Identifier, "_x"),
+                (TokenKind::Mapping, ":"),
+                (TokenKind::Identifier, "i32"),
+                (TokenKind::BracketEnd, "}"),
+            ];
+
+            let mut tokenizer = Tokenizer::new("test.def".to_string(), &text);
+            for (token_kind, orig) in correct_tokens {
+                let token = tokenizer.request(token_kind.clone()).unwrap();
+                assert_eq!(token.kind, token_kind);
+                assert_eq!(token.orig, orig);
+            }
+            assert!(!tokenizer.exists_next());
+        }
+
+        #[test]
+        fn expect_unexpected_character() {
+            let texts = [
+                ("data Box #", '#', "test.def:1:10"),
+                ("data\n  箱 $", '$', "test.def:2:5"),
+                ("data x \u{0301}", '\u{0301}', "test.def:1:8"),
+            ];
+            for (text, c, loc) in texts {
+                let mut tokenizer = Tokenizer::new("test.def".to_string(), text);
+                tokenizer.request(TokenKind::Data).unwrap();
+                tokenizer.request(TokenKind::Identifier).unwrap();
+                let err = tokenizer.peek().unwrap_err().downcast::<PError>().unwrap();
+                assert!(
+                    matches!(err.kind(), PErrorKind::UnexpectedCharacter(found) if *found == c)
+                );
+                assert_eq!(format!("{}", err.location()), loc);
+            }
+
+            // 不明な文字を読み捨てた後は，次のトークンから再開できる
+            let text = "data # Box".to_string();
+            let mut tokenizer = Tokenizer::new("test.def".to_string(), &text);
+            tokenizer.request(TokenKind::Data).unwrap();
+            assert!(tokenizer.peek().is_err());
+            let token = tokenizer.request(TokenKind::Identifier).unwrap();
+            assert_eq!(token.orig, "Box");
+        }
+
         #[test]
         fn expect_all_ng() {
             let text = "data".to_string();
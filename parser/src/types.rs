@@ -1,39 +1,74 @@
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter};
 
-use serde::de::Deserializer;
+use serde::de::{Deserializer, Error};
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
 
 use super::name::Name;
 
+// ※paramsは型引数 (List<T>のTなど)
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Type {
     pub kind: TypeKind,
     pub refs: Option<Name>,
+    #[serde(default)]
+    pub params: Vec<Type>,
 }
 
 impl Type {
     pub fn new(kind: TypeKind, name: Option<Name>) -> Type {
-        Type { kind, refs: name }
+        Type {
+            kind,
+            refs: name,
+            params: vec![],
+        }
     }
 
-    pub fn new_unsovled_nohint() -> Type {
-        Type {
-            kind: TypeKind::UnsolvedNoHint,
-            refs: None,
+    pub fn with_params(mut self, params: Vec<Type>) -> Type {
+        self.params = params;
+        self
+    }
+
+    // 型として適合するかどうか
+    // ※anyはすべての型と適合する (型引数の中に含まれる場合も同様)
+    pub fn is_compatible(&self, other: &Type) -> bool {
+        if self.kind == TypeKind::Any || other.kind == TypeKind::Any {
+            return true;
         }
+        self.kind == other.kind
+            && self.refs == other.refs
+            && self.params.len() == other.params.len()
+            && self
+                .params
+                .iter()
+                .zip(other.params.iter())
+                .all(|(a, b)| a.is_compatible(b))
     }
 }
 
-impl From<String> for Type {
-    fn from(name: String) -> Type {
-        Type {
-            kind: TypeKind::from(name),
-            refs: None,
+// 記述された形式(Map<string, List<User>>)で表示する
+// ※ユーザ定義型, 型パラメータは参照先の名前で表示する
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match (&self.kind, &self.refs) {
+            (TypeKind::Data | TypeKind::Enum | TypeKind::Param, Some(refs)) => {
+                write!(f, "{}", refs.name)?
+            }
+            (kind, _) => write!(f, "{:?}", kind)?,
         }
+        if !self.params.is_empty() {
+            let params = self
+                .params
+                .iter()
+                .map(|param| param.to_string())
+                .collect::<Vec<String>>();
+            write!(f, "<{}>", params.join(", "))?;
+        }
+        Ok(())
     }
 }
 
+// ※未解決の型は unchecked::TypeKind でのみ表現される
 #[derive(Clone, PartialEq, Eq)]
 pub enum TypeKind {
     /* 無 */
@@ -41,43 +76,71 @@ pub enum TypeKind {
 
     /* プリミティブ型 */
     Int32,
+    Int64,
     UInt32,
+    UInt64,
     Float32,
+    Float64,
     Boolean,
     Char,
+    String,
+    Bytes,
+
+    /* 任意の型 (すべての型と適合する) */
+    Any,
 
     /* ユーザ定義型 */
     Data,
+    Enum,
 
-    /* パーサ用 (解決後のSysDCSystemには含まれない) */
-    Unsolved(String),
-    UnsolvedNoHint,
+    /* 型パラメータ (data Page<T> のTなど) */
+    Param,
+
+    /* コレクション型 */
+    List,
+    Map,
+    Option,
 }
 
 impl TypeKind {
+    const NAMES: &'static [&'static str] = &[
+        "void", "i32", "i64", "u32", "u64", "f32", "f64", "bool", "char", "string", "bytes", "any",
+        "Data", "Enum", "Param", "List", "Map", "Option",
+    ];
+
     pub fn is_primitive(&self) -> bool {
-        matches!(
+        !matches!(
             self,
-            TypeKind::Void
-                | TypeKind::Int32
-                | TypeKind::UInt32
-                | TypeKind::Float32
-                | TypeKind::Boolean
-                | TypeKind::Char
+            TypeKind::Data
+                | TypeKind::Enum
+                | TypeKind::Param
+                | TypeKind::List
+                | TypeKind::Map
+                | TypeKind::Option
         )
     }
-}
 
-impl From<String> for TypeKind {
-    fn from(name: String) -> TypeKind {
-        match name.as_str() {
-            "void" => TypeKind::Void,
-            "i32" => TypeKind::Int32,
-            "u32" => TypeKind::UInt32,
-            "f32" => TypeKind::Float32,
-            "bool" => TypeKind::Boolean,
-            "char" => TypeKind::Char,
-            _ => TypeKind::Unsolved(name),
+    fn from_name(name: &str) -> Option<TypeKind> {
+        match name {
+            "void" => Some(TypeKind::Void),
+            "i32" => Some(TypeKind::Int32),
+            "i64" => Some(TypeKind::Int64),
+            "u32" => Some(TypeKind::UInt32),
+            "u64" => Some(TypeKind::UInt64),
+            "f32" => Some(TypeKind::Float32),
+            "f64" => Some(TypeKind::Float64),
+            "bool" => Some(TypeKind::Boolean),
+            "char" => Some(TypeKind::Char),
+            "string" => Some(TypeKind::String),
+            "bytes" => Some(TypeKind::Bytes),
+            "any" => Some(TypeKind::Any),
+            "Data" => Some(TypeKind::Data),
+            "Enum" => Some(TypeKind::Enum),
+            "Param" => Some(TypeKind::Param),
+            "List" => Some(TypeKind::List),
+            "Map" => Some(TypeKind::Map),
+            "Option" => Some(TypeKind::Option),
+            _ => None,
         }
     }
 }
@@ -87,13 +150,22 @@ impl Debug for TypeKind {
         match self {
             TypeKind::Void => write!(f, "void"),
             TypeKind::Int32 => write!(f, "i32"),
+            TypeKind::Int64 => write!(f, "i64"),
             TypeKind::UInt32 => write!(f, "u32"),
+            TypeKind::UInt64 => write!(f, "u64"),
             TypeKind::Float32 => write!(f, "f32"),
+            TypeKind::Float64 => write!(f, "f64"),
             TypeKind::Boolean => write!(f, "bool"),
             TypeKind::Char => write!(f, "char"),
+            TypeKind::String => write!(f, "string"),
+            TypeKind::Bytes => write!(f, "bytes"),
+            TypeKind::Any => write!(f, "any"),
             TypeKind::Data => write!(f, "Data"),
-            TypeKind::Unsolved(hint) => write!(f, "{}", hint),
-            TypeKind::UnsolvedNoHint => write!(f, "UnsolvedNoHint"),
+            TypeKind::Enum => write!(f, "Enum"),
+            TypeKind::Param => write!(f, "Param"),
+            TypeKind::List => write!(f, "List"),
+            TypeKind::Map => write!(f, "Map"),
+            TypeKind::Option => write!(f, "Option"),
         }
     }
 }
@@ -103,12 +175,7 @@ impl Serialize for TypeKind {
     where
         S: Serializer,
     {
-        match self {
-            TypeKind::Unsolved(_) | TypeKind::UnsolvedNoHint => {
-                panic!("[ERROR] Cannot serialize object containing unsolved types.")
-            }
-            _ => serializer.serialize_str(&format!("{:?}", self)),
-        }
+        serializer.serialize_str(&format!("{:?}", self))
     }
 }
 
@@ -118,9 +185,159 @@ impl<'de> Deserialize<'de> for TypeKind {
         D: Deserializer<'de>,
     {
         let skind = String::deserialize(deserializer)?;
-        match TypeKind::from(skind) {
-            TypeKind::Unsolved(_) => Ok(TypeKind::Data),
-            kind => Ok(kind),
+        TypeKind::from_name(&skind)
+            .ok_or_else(|| D::Error::unknown_variant(&skind, TypeKind::NAMES))
+    }
+}
+
+pub mod unchecked {
+    use std::fmt::{Debug, Display, Formatter};
+
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct Type {
+        pub kind: TypeKind,
+        pub params: Vec<Type>,
+    }
+
+    impl Type {
+        pub fn new(kind: TypeKind) -> Type {
+            Type {
+                kind,
+                params: vec![],
+            }
+        }
+
+        pub fn new_unsovled_nohint() -> Type {
+            Type::new(TypeKind::UnsolvedNoHint)
+        }
+
+        pub fn with_params(mut self, params: Vec<Type>) -> Type {
+            self.params = params;
+            self
+        }
+    }
+
+    impl From<String> for Type {
+        fn from(name: String) -> Type {
+            Type::new(TypeKind::from(name))
+        }
+    }
+
+    impl Display for Type {
+        fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "{:?}", self.kind)?;
+            if !self.params.is_empty() {
+                let params = self
+                    .params
+                    .iter()
+                    .map(|param| param.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "<{}>", params.join(", "))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Debug for Type {
+        fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "{}", self)
+        }
+    }
+
+    #[derive(Clone, PartialEq, Eq)]
+    pub enum TypeKind {
+        /* 無 */
+        Void,
+
+        /* プリミティブ型 */
+        Int32,
+        Int64,
+        UInt32,
+        UInt64,
+        Float32,
+        Float64,
+        Boolean,
+        Char,
+        String,
+        Bytes,
+
+        /* 任意の型 */
+        Any,
+
+        /* コレクション型 */
+        List,
+        Map,
+        Option,
+
+        /* 未解決 (名前解決によってユーザ定義型などに置き換えられる) */
+        Unsolved(String),
+        UnsolvedNoHint,
+    }
+
+    impl TypeKind {
+        // プリミティブ型(およびany)であれば，対応する検査済みの型を返す
+        pub fn as_primitive(&self) -> Option<super::TypeKind> {
+            match self {
+                TypeKind::Void => Some(super::TypeKind::Void),
+                TypeKind::Int32 => Some(super::TypeKind::Int32),
+                TypeKind::Int64 => Some(super::TypeKind::Int64),
+                TypeKind::UInt32 => Some(super::TypeKind::UInt32),
+                TypeKind::UInt64 => Some(super::TypeKind::UInt64),
+                TypeKind::Float32 => Some(super::TypeKind::Float32),
+                TypeKind::Float64 => Some(super::TypeKind::Float64),
+                TypeKind::Boolean => Some(super::TypeKind::Boolean),
+                TypeKind::Char => Some(super::TypeKind::Char),
+                TypeKind::String => Some(super::TypeKind::String),
+                TypeKind::Bytes => Some(super::TypeKind::Bytes),
+                TypeKind::Any => Some(super::TypeKind::Any),
+                _ => None,
+            }
+        }
+
+        // コレクション型であれば，対応する検査済みの型と型引数の数を返す
+        pub fn as_collection(&self) -> Option<(super::TypeKind, usize)> {
+            match self {
+                TypeKind::List => Some((super::TypeKind::List, 1)),
+                TypeKind::Map => Some((super::TypeKind::Map, 2)),
+                TypeKind::Option => Some((super::TypeKind::Option, 1)),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<String> for TypeKind {
+        fn from(name: String) -> TypeKind {
+            match name.as_str() {
+                "void" => TypeKind::Void,
+                "i32" => TypeKind::Int32,
+                "i64" => TypeKind::Int64,
+                "u32" => TypeKind::UInt32,
+                "u64" => TypeKind::UInt64,
+                "f32" => TypeKind::Float32,
+                "f64" => TypeKind::Float64,
+                "bool" => TypeKind::Boolean,
+                "char" => TypeKind::Char,
+                "string" => TypeKind::String,
+                "bytes" => TypeKind::Bytes,
+                "any" => TypeKind::Any,
+                "List" => TypeKind::List,
+                "Map" => TypeKind::Map,
+                "Option" => TypeKind::Option,
+                _ => TypeKind::Unsolved(name),
+            }
+        }
+    }
+
+    impl Debug for TypeKind {
+        fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+            match self {
+                TypeKind::Unsolved(hint) => write!(f, "{}", hint),
+                TypeKind::UnsolvedNoHint => write!(f, "UnsolvedNoHint"),
+                kind => match kind.as_collection() {
+                    Some((kind, _)) => write!(f, "{:?}", kind),
+                    None => write!(f, "{:?}", kind.as_primitive().unwrap()),
+                },
+            }
         }
     }
 }
@@ -131,7 +348,8 @@ mod test {
     use rmp_serde::Serializer;
     use serde::Serialize;
 
-    use super::TypeKind;
+    use super::{unchecked, Type, TypeKind};
+    use crate::name::Name;
 
     macro_rules! check_serialize {
         ($target:ty, $obj:expr) => {
@@ -147,22 +365,46 @@ mod test {
     fn default() {
         check_serialize!(TypeKind, TypeKind::Void);
         check_serialize!(TypeKind, TypeKind::Int32);
+        check_serialize!(TypeKind, TypeKind::Int64);
         check_serialize!(TypeKind, TypeKind::UInt32);
+        check_serialize!(TypeKind, TypeKind::UInt64);
         check_serialize!(TypeKind, TypeKind::Float32);
+        check_serialize!(TypeKind, TypeKind::Float64);
         check_serialize!(TypeKind, TypeKind::Boolean);
         check_serialize!(TypeKind, TypeKind::Char);
+        check_serialize!(TypeKind, TypeKind::String);
+        check_serialize!(TypeKind, TypeKind::Bytes);
+        check_serialize!(TypeKind, TypeKind::Any);
         check_serialize!(TypeKind, TypeKind::Data);
+        check_serialize!(TypeKind, TypeKind::Enum);
+        check_serialize!(TypeKind, TypeKind::Param);
+        check_serialize!(TypeKind, TypeKind::List);
+        check_serialize!(TypeKind, TypeKind::Map);
+        check_serialize!(TypeKind, TypeKind::Option);
     }
 
     #[test]
-    #[should_panic]
-    fn primitive_unsolved_1() {
-        check_serialize!(TypeKind, TypeKind::Unsolved("aaa".to_string()));
+    fn deserialize_unknown_kind() {
+        let mut serialized = vec![];
+        "UnknownType"
+            .serialize(&mut Serializer::new(&mut serialized))
+            .unwrap();
+        let err = rmp_serde::from_slice::<TypeKind>(&serialized[..]).unwrap_err();
+        assert!(format!("{}", err).contains("UnknownType"), "{}", err);
     }
 
     #[test]
-    #[should_panic]
-    fn primitive_unsolved_2() {
-        check_serialize!(TypeKind, TypeKind::UnsolvedNoHint);
+    fn display() {
+        let user = Name::new(&Name::new_root(), "User".to_string());
+        let types = Type::new(TypeKind::Map, None).with_params(vec![
+            Type::new(TypeKind::String, None),
+            Type::new(TypeKind::List, None)
+                .with_params(vec![Type::new(TypeKind::Data, Some(user))]),
+        ]);
+        assert_eq!(format!("{}", types), "Map<string, List<User>>");
+
+        let types = unchecked::Type::from("Page".to_string())
+            .with_params(vec![unchecked::Type::from("i32".to_string())]);
+        assert_eq!(format!("{}", types), "Page<i32>");
     }
 }
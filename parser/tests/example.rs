@@ -11,7 +11,6 @@ fn parse_example_box() {
 fn parse_example_logger() {
     parse_files(&[
         "../example/logger/logger.def",
-        "../example/logger/std.def",
         "../example/logger/std/io.def",
         "../example/logger/std/time.def",
     ])
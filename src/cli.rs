@@ -1,6 +1,7 @@
 mod parse;
 mod exec;
 mod tool;
+mod explain;
 
 use std::process::exit;
 
@@ -26,6 +27,9 @@ enum AppSub {
 
     /// Manage tools
     tool(tool::ToolCmd),
+
+    /// Show a detailed explanation of an error code
+    explain(explain::ExplainCmd),
 }
 
 impl App {
@@ -34,11 +38,12 @@ impl App {
             AppSub::parse(cmd) => cmd.run(),
             AppSub::exec(cmd) => cmd.run(),
             AppSub::tool(cmd) => cmd.run(),
+            AppSub::explain(cmd) => cmd.run(),
         };
         match result {
             Ok(_) => exit(0),
             Err(err) => {
-                println!("[ERROR] {}", err);
+                eprintln!("[ERROR] {}", err);
                 exit(1);
             }
         }
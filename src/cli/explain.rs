@@ -0,0 +1,29 @@
+use clap::Parser;
+use thiserror::Error;
+
+use sysdc_parser::error::explain::explain;
+
+#[derive(Debug, Error)]
+enum ExplainError {
+    #[error("Error code \"{0}\" not found")]
+    CodeNotFound(String),
+}
+
+#[derive(Parser)]
+pub struct ExplainCmd {
+    /// Error code (e.g. E0001)
+    #[clap(required = true)]
+    code: String,
+}
+
+impl ExplainCmd {
+    pub fn run(&self) -> anyhow::Result<()> {
+        match explain(&self.code) {
+            Some(explanation) => {
+                print!("{}", explanation);
+                Ok(())
+            }
+            None => Err(ExplainError::CodeNotFound(self.code.clone()).into()),
+        }
+    }
+}
@@ -1,14 +1,43 @@
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::io::{stdout, IsTerminal, Write};
 
-use clap::Parser;
+use clap::{ArgEnum, Parser};
 use rmp_serde::Serializer;
 use serde::Serialize;
+use thiserror::Error;
 
+use sysdc_parser::lint::{Lint, LintConfig, LintLevel};
 use sysdc_parser::structure::SysDCSystem;
 use sysdc_parser::Parser as SParser;
 
+#[derive(Debug, Error)]
+enum ParseError {
+    #[error("Could not parse due to {0} previous error(s)")]
+    FoundError(usize),
+
+    #[error("Could not parse due to {0} previous warning(s) (--deny-warnings)")]
+    FoundWarning(usize),
+
+    #[error("Lint \"{0}\" not found")]
+    LintNotFound(String),
+}
+
+#[derive(Clone, ArgEnum)]
+#[allow(non_camel_case_types)]
+enum ColorChoice {
+    auto,
+    always,
+    never,
+}
+
+#[derive(Clone, PartialEq, Eq, ArgEnum)]
+#[allow(non_camel_case_types)]
+enum MessageFormat {
+    human,
+    json,
+}
+
 #[derive(Parser)]
 pub struct ParseCmd {
     #[clap(required = true)]
@@ -16,6 +45,26 @@ pub struct ParseCmd {
 
     #[clap(short, long, default_value = "out.sysdc")]
     output: String,
+
+    /// Coloring of error messages
+    #[clap(long, arg_enum, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Format of error messages (json: one object per line)
+    #[clap(long, arg_enum, default_value = "human")]
+    message_format: MessageFormat,
+
+    /// Disable a lint (e.g. unused_argument)
+    #[clap(short = 'A', long)]
+    allow: Vec<String>,
+
+    /// Report a lint as an error (e.g. import_cycle)
+    #[clap(short = 'D', long)]
+    deny: Vec<String>,
+
+    /// Treat warnings as errors
+    #[clap(long)]
+    deny_warnings: bool,
 }
 
 impl ParseCmd {
@@ -25,21 +74,75 @@ impl ParseCmd {
 
     fn read_files(&self) -> anyhow::Result<SysDCSystem> {
         let mut load_unit_cnt = 0;
-        let mut parser = SParser::default();
+        let mut parser = SParser::default().with_lint_config(self.lint_config()?);
         for filename in &self.input {
             for entry in glob::glob(filename)?.flatten() {
                 if entry.is_file() {
-                    let filename = entry.file_name().unwrap().to_str().unwrap().to_string();
+                    // ※同名のファイルを区別するため，位置情報には指定されたパスをそのまま用いる
+                    let filename = entry.to_str().unwrap().to_string();
                     let program = fs::read_to_string(&entry)?;
-                    println!("Loading: {}", filename);
-                    parser.parse(filename, &program)?;
+                    self.report(&format!("Loading: {}", filename));
+                    // エラーはまとめて報告するため，ここでは読み込みを中断しない
+                    let _ = parser.parse(filename, &program);
                     load_unit_cnt += 1;
                 }
             }
         }
-        let system = parser.check()?;
-        println!("{} units loaded!", load_unit_cnt);
-        Ok(system)
+
+        let renderer = parser.renderer().with_color(self.use_color());
+        let (system, diagnostics) = parser.check_with_diagnostics();
+        for diagnostic in &diagnostics {
+            match self.message_format {
+                MessageFormat::human => println!("{}\n", renderer.render(diagnostic)),
+                MessageFormat::json => println!("{}", serde_json::to_string(diagnostic)?),
+            }
+        }
+        if !diagnostics.is_empty() {
+            self.report("For more information about an error, try `sysdc explain <code>`.");
+        }
+
+        let warn_cnt = diagnostics.iter().filter(|d| !d.is_error()).count();
+        match system {
+            Some(_) if self.deny_warnings && warn_cnt > 0 => {
+                Err(ParseError::FoundWarning(warn_cnt).into())
+            }
+            Some(system) => {
+                self.report(&format!("{} units loaded!", load_unit_cnt));
+                Ok(system)
+            }
+            None => {
+                let err_cnt = diagnostics.iter().filter(|d| d.is_error()).count();
+                Err(ParseError::FoundError(err_cnt).into())
+            }
+        }
+    }
+
+    fn lint_config(&self) -> anyhow::Result<LintConfig> {
+        let mut config = LintConfig::new();
+        let allow = self.allow.iter().map(|name| (name, LintLevel::Allow));
+        let deny = self.deny.iter().map(|name| (name, LintLevel::Deny));
+        for (name, level) in allow.chain(deny) {
+            match Lint::from_name(name) {
+                Some(lint) => config = config.with_lint_level(lint, level),
+                None => return Err(ParseError::LintNotFound(name.clone()).into()),
+            }
+        }
+        Ok(config)
+    }
+
+    // 進捗の表示 (JSON形式での出力時は，出力を診断情報のみに限るため表示しない)
+    fn report(&self, message: &str) {
+        if self.message_format == MessageFormat::human {
+            println!("{}", message);
+        }
+    }
+
+    fn use_color(&self) -> bool {
+        match self.color {
+            ColorChoice::auto => stdout().is_terminal(),
+            ColorChoice::always => true,
+            ColorChoice::never => false,
+        }
     }
 
     fn save_system(&self, system: SysDCSystem) -> anyhow::Result<()> {
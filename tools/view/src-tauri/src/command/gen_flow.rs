@@ -105,7 +105,10 @@ fn gen_func_flow(func: &SysDCFunction) -> ReactFlowDesign {
 }
 
 fn gen_annotation_flow(func: &SysDCFunction, annotation: &SysDCAnnotation) -> ReactFlowDesign {
-    if let SysDCAnnotation::Affect { func: afunc, args } = annotation {
+    if let SysDCAnnotation::Affect {
+        func: afunc, args, ..
+    } = annotation
+    {
         return gen_annotation_affect_flow(&func.name, &afunc.0, args);
     }
 
@@ -127,7 +130,10 @@ fn gen_annotation_flow(func: &SysDCFunction, annotation: &SysDCAnnotation) -> Re
             let (mut nodes, mut edges) = details
                 .iter()
                 .filter_map(|detail| {
-                    if let SysDCSpawnDetail::LetTo { name, func, args } = detail {
+                    if let SysDCSpawnDetail::LetTo {
+                        name, func, args, ..
+                    } = detail
+                    {
                         Some(gen_annotation_spawn_flow(
                             &(name.clone(), func.1.clone()),
                             &func.0,
@@ -1,7 +1,7 @@
 use serde::Serialize;
 
 use sysdc_parser::name::Name;
-use sysdc_parser::types::Type;
+use sysdc_parser::types::{Type, TypeKind};
 
 pub type ReactFlowDesign = (Vec<ReactFlowNode>, Vec<ReactFlowEdge>);
 
@@ -53,7 +53,7 @@ impl ReactFlowNode {
         let data = if let Some(types) = types {
             (name.clone(), types.clone())
         } else {
-            (name.clone(), Type::from("void".to_string()))
+            (name.clone(), Type::new(TypeKind::Void, None))
         };
 
         ReactFlowNode {
@@ -127,7 +127,7 @@ mod test {
 
     use super::{ReactFlowEdge, ReactFlowNode, ReactFlowNodeData, ReactFlowNodeKind};
     use sysdc_parser::name::Name;
-    use sysdc_parser::types::Type;
+    use sysdc_parser::types::{Type, TypeKind};
 
     #[test]
     fn node_serialize_1() {
@@ -137,12 +137,12 @@ mod test {
             Some(".0".to_string()),
             ReactFlowNodeData::new(Some((
                 Name::new(&Name::new_root(), "test".to_string()),
-                Type::from("void".to_string()),
+                Type::new(TypeKind::Void, None),
             ))),
         );
         compare(
             has_parent_node,
-            "{\"id\":\".0.test\",\"type\":\"Var\",\"parentNode\":\".0\",\"data\":{\"name\":{\"name\":\"test\",\"namespace\":\".0\"},\"type\":{\"kind\":\"void\",\"refs\":null}}}",
+            "{\"id\":\".0.test\",\"type\":\"Var\",\"parentNode\":\".0\",\"data\":{\"name\":{\"name\":\"test\",\"namespace\":\".0\",\"loc\":{\"filename\":null,\"begin\":null,\"end\":null},\"doc\":null},\"type\":{\"kind\":\"void\",\"refs\":null,\"params\":[]}}}",
         );
     }
 
@@ -154,12 +154,12 @@ mod test {
             None,
             ReactFlowNodeData::new(Some((
                 Name::new(&Name::new_root(), "test".to_string()),
-                Type::from("void".to_string()),
+                Type::new(TypeKind::Void, None),
             ))),
         );
         compare(
             hasnt_parent_node,
-        "{\"id\":\".0.test\",\"type\":\"Var\",\"data\":{\"name\":{\"name\":\"test\",\"namespace\":\".0\"},\"type\":{\"kind\":\"void\",\"refs\":null}}}",
+        "{\"id\":\".0.test\",\"type\":\"Var\",\"data\":{\"name\":{\"name\":\"test\",\"namespace\":\".0\",\"loc\":{\"filename\":null,\"begin\":null,\"end\":null},\"doc\":null},\"type\":{\"kind\":\"void\",\"refs\":null,\"params\":[]}}}",
         );
     }
 
@@ -19,9 +19,17 @@ export const CUSTOM_NODE_TYPES = {
 
 export default CUSTOM_NODE_TYPES;
 
+function typeName(type): string {
+    const name = type.refs != null ? type.refs.name : type.kind;
+    if (type.params == null || type.params.length == 0) {
+        return name;
+    }
+    return name + "<" + type.params.map(typeName).join(", ") + ">";
+}
+
 export function UnitNode({ data }) {
     return (
-        <div className={styles.Unit}>
+        <div className={styles.Unit} title={data.name.doc ?? undefined}>
             <h1>{data.name.name}</h1>
         </div>
     );
@@ -29,7 +37,7 @@ export function UnitNode({ data }) {
 
 export function ModuleNode({ data }) {
     return (
-        <div className={styles.Module}>
+        <div className={styles.Module} title={data.name.doc ?? undefined}>
             <h1>{data.name.name}</h1>
         </div>
     );
@@ -37,7 +45,7 @@ export function ModuleNode({ data }) {
 
 export function FunctionNode({ data }) {
     return (
-        <div className={styles.Function}>
+        <div className={styles.Function} title={data.name.doc ?? undefined}>
             <Handle type="target" position={Position.Top}/>
             <h1>{data.name.name}</h1>
             <Handle type="source" position={Position.Bottom}/>
@@ -47,7 +55,7 @@ export function FunctionNode({ data }) {
 
 export function ProcedureNode({ data }) {
     return (
-        <div className={styles.Procedure}>
+        <div className={styles.Procedure} title={data.name.doc ?? undefined}>
             <Handle type="target" position={Position.Top}/>
             <h1>{data.name.name}</h1>
         </div>
@@ -58,13 +66,13 @@ export function ArgumentNode({ data }) {
     return (
         <div>
             Argument
-            <div className={styles.Argument}>
+            <div className={styles.Argument} title={data.name.doc ?? undefined}>
                 <div className={styles.FixedHandle}>
                     <Handle type="target" position={Position.Top}/>
                 </div>
                 <p className={styles.Name}>{data.name.name}</p>
                 <br/>
-                <p className={styles.Type}>({data.type.kind === "Data" ? data.type.refs.name : data.type.kind})</p>
+                <p className={styles.Type}>({typeName(data.type)})</p>
                 <div className={styles.FixedHandle}>
                     <Handle type="source" position={Position.Bottom}/>
                 </div>
@@ -79,7 +87,7 @@ export function VarNode({ data }) {
             <Handle type="target" position={Position.Top}/>
             <p className={styles.Name}>{data.name.name}</p>
             <br/>
-            <p className={styles.Type}>({data.type.kind === "Data" ? data.type.refs.name : data.type.kind})</p>
+            <p className={styles.Type}>({typeName(data.type)})</p>
             <Handle type="source" position={Position.Bottom}/>
         </div>
     );
@@ -91,7 +99,7 @@ export function DeadVarNode({ data }) {
             <Handle type="target" position={Position.Top}/>
             <p className={styles.Name}>{data.name.name}</p>
             <br/>
-            <p className={styles.Type}>({data.type.kind === "Data" ? data.type.refs.name : data.type.kind})</p>
+            <p className={styles.Type}>({typeName(data.type)})</p>
             <Handle type="source" position={Position.Bottom}/>
         </div>
     );
@@ -107,7 +115,7 @@ export function ReturnVarNode({ data }) {
                 </div>
                 <p className={styles.Name}>{data.name.name}</p>
                 <br/>
-                <p className={styles.Type}>({data.type.kind === "Data" ? data.type.refs.name : data.type.kind})</p>
+                <p className={styles.Type}>({typeName(data.type)})</p>
                 <div className={styles.FixedHandle}>
                     <Handle type="source" position={Position.Bottom}/>
                 </div>
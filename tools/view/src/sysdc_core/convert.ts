@@ -1,6 +1,6 @@
 import {
-    Name, Type,
-    SysDCSystem, SysDCUnit, SysDCData, SysDCModule, SysDCFunction, SysDCAnnotation, SysDCSpawnDetail
+    Location, Name, Type,
+    SysDCSystem, SysDCUnit, SysDCData, SysDCEnum, SysDCVariant, SysDCInterface, SysDCModule, SysDCFunction, SysDCAnnotation, SysDCSpawnDetail
 } from "./structure";
 
 export default convert;
@@ -15,30 +15,69 @@ function convertUnit(obj: object): SysDCUnit {
     return {
         name: convertName(obj["name"]),
         data: obj["data"].map(convertData),
-        modules: obj["modules"].map(convertModule)
+        enums: obj["enums"].map(convertEnum),
+        interfaces: obj["interfaces"].map(convertInterface),
+        modules: obj["modules"].map(convertModule),
+        loc: convertLocation(obj["loc"])
     };
 }
 
 function convertData(obj: object): SysDCData {
     return {
         name: convertName(obj["name"]),
-        members: obj["members"].map(convertNameType)
+        visibility: obj["visibility"],
+        params: obj["params"].map(convertName),
+        extends: obj["extends"].map(convertType),
+        members: obj["members"].map(convertNameType),
+        loc: convertLocation(obj["loc"])
+    };
+}
+
+function convertEnum(obj: object): SysDCEnum {
+    return {
+        name: convertName(obj["name"]),
+        visibility: obj["visibility"],
+        variants: obj["variants"].map(convertVariant),
+        loc: convertLocation(obj["loc"])
+    };
+}
+
+function convertVariant(obj: object): SysDCVariant {
+    return {
+        name: convertName(obj["name"]),
+        members: obj["members"].map(convertNameType),
+        loc: convertLocation(obj["loc"])
+    };
+}
+
+function convertInterface(obj: object): SysDCInterface {
+    return {
+        name: convertName(obj["name"]),
+        visibility: obj["visibility"],
+        functions: obj["functions"].map(convertFunction),
+        loc: convertLocation(obj["loc"])
     };
 }
 
 function convertModule(obj: object): SysDCModule {
     return {
         name: convertName(obj["name"]),
-        functions: obj["functions"].map(convertFunction)
+        visibility: obj["visibility"],
+        params: obj["params"].map(convertName),
+        implements: obj["implements"].map(convertName),
+        functions: obj["functions"].map(convertFunction),
+        loc: convertLocation(obj["loc"])
     };
 }
 
 function convertFunction(obj: object): SysDCFunction  {
     return {
         name: convertName(obj["name"]),
+        visibility: obj["visibility"],
         args: obj["args"].map(convertNameType),
         return: convertNameType(obj["returns"]),
-        annotations: obj["annotations"].map(convertAnnotation)
+        annotations: obj["annotations"].map(convertAnnotation),
+        loc: convertLocation(obj["loc"])
     };
 }
 
@@ -83,15 +122,28 @@ function convertName(obj: object): Name {
     return {
         fname: obj["namespace"]+"."+obj["name"],
         name: obj["name"],
-        namespace: obj["namespace"]
+        namespace: obj["namespace"],
+        loc: convertLocation(obj["loc"]),
+        doc: obj["doc"] ?? null
+    };
+}
+
+function convertLocation(obj: object): Location {
+    return {
+        filename: obj["filename"],
+        begin: obj["begin"],
+        end: obj["end"]
     };
 }
 
 function convertType(obj: object): Type {
-    if (obj["refs"] == null) {
-        return obj["kind"];
+    const name = obj["refs"] == null
+        ? obj["kind"]
+        : obj["refs"]["namespace"] + "." + obj["refs"]["name"];
+    if (obj["params"] == null || obj["params"].length == 0) {
+        return name;
     }
-    return obj["refs"]["namespace"] + "." + obj["refs"]["name"];
+    return name + "<" + obj["params"].map(convertType).join(", ") + ">";
 }
 
 function convertNameType(obj: object): [Name, Type] {
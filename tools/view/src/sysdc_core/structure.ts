@@ -1,13 +1,23 @@
 export default SysDCSystem;
 
+export type Location = {
+    readonly filename: string | null,
+    readonly begin: [number, number] | null,
+    readonly end: [number, number] | null
+}
+
 export type Name = {
     readonly fname: string,
     readonly name: string,
-    readonly namespace: string
+    readonly namespace: string,
+    readonly loc: Location,
+    readonly doc: string | null
 }
 
 export type Type = string;
 
+export type Visibility = "Private" | "Public" | { readonly Restricted: string };
+
 export type SysDCSystem = {
     readonly units: SysDCUnit[]
 }
@@ -15,24 +25,57 @@ export type SysDCSystem = {
 export type SysDCUnit = {
     readonly name: Name,
     readonly data: SysDCData[],
-    readonly modules: SysDCModule[]
+    readonly enums: SysDCEnum[],
+    readonly interfaces: SysDCInterface[],
+    readonly modules: SysDCModule[],
+    readonly loc: Location
 }
 
 export type SysDCData = {
     readonly name: Name,
-    readonly members: [Name, Type][]
+    readonly visibility: Visibility,
+    readonly params: Name[],
+    readonly extends: Type[],
+    readonly members: [Name, Type][],
+    readonly loc: Location
+}
+
+export type SysDCEnum = {
+    readonly name: Name,
+    readonly visibility: Visibility,
+    readonly variants: SysDCVariant[],
+    readonly loc: Location
+}
+
+export type SysDCVariant = {
+    readonly name: Name,
+    readonly members: [Name, Type][],
+    readonly loc: Location
+}
+
+export type SysDCInterface = {
+    readonly name: Name,
+    readonly visibility: Visibility,
+    readonly functions: SysDCFunction[],
+    readonly loc: Location
 }
 
 export type SysDCModule = {
     readonly name: Name,
-    readonly functions: SysDCFunction[]
+    readonly visibility: Visibility,
+    readonly params: Name[],
+    readonly implements: Name[],
+    readonly functions: SysDCFunction[],
+    readonly loc: Location
 }
 
 export type SysDCFunction = {
     readonly name: Name,
+    readonly visibility: Visibility,
     readonly args: [Name, Type][],
     readonly return: [Name, Type],
-    readonly annotations: SysDCAnnotation[]
+    readonly annotations: SysDCAnnotation[],
+    readonly loc: Location
 }
 
 export type SysDCAnnotation =